@@ -0,0 +1,165 @@
+use crate::{
+    direction::Direction, error::Error, explosion::ExplosionEvent, map::Map, map_elements::Item,
+    point::Point,
+};
+
+/// Nodo del arbol de reacciones en cadena. Cada nodo es una bomba que exploto y sus hijos son las bombas que detono su explosion.
+/// # Attributes
+/// * `point` - Punto donde se encontraba la bomba.
+/// * `bomb` - Bomba que exploto.
+/// * `trigger` - Direccion de la rafaga que la alcanzo y distancia a la bomba que la detono. Es None para la bomba inicial.
+/// * `children` - Bombas detonadas por esta bomba, en el orden en que fueron alcanzadas.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChainNode {
+    pub point: Point,
+    pub bomb: Item,
+    pub trigger: Option<(Direction, u32)>,
+    pub children: Vec<ChainNode>,
+}
+
+impl ChainNode {
+    /// Calcula el arbol de reacciones en cadena que produce detonar una bomba, sin modificar el mapa.
+    /// # Arguments
+    /// * `map` - Mapa sobre el que se simula la explosion
+    /// * `start` - Punto de la bomba a detonar
+    /// # Returns
+    /// * `Result<ChainNode, Error>` - Raiz del arbol, o el error en caso de que no haya una bomba en el punto
+    pub fn simulate(map: &Map, start: &Point) -> Result<ChainNode, Error> {
+        let mut simulated = map.clone();
+        let events = simulated.detonate_bomb_traced(start)?;
+        Ok(ChainNode::from_events(map, start, &events))
+    }
+
+    /// Construye el arbol de reacciones en cadena a partir de los eventos de una explosion.
+    /// # Arguments
+    /// * `map` - Mapa previo a la explosion, del que se obtienen las bombas
+    /// * `start` - Punto de la bomba inicial
+    /// * `events` - Eventos de la explosion
+    /// # Returns
+    /// * `ChainNode` - Raiz del arbol
+    pub fn from_events(map: &Map, start: &Point, events: &[ExplosionEvent]) -> ChainNode {
+        ChainNode::build(map, *start, None, events)
+    }
+
+    fn build(
+        map: &Map,
+        point: Point,
+        trigger: Option<(Direction, u32)>,
+        events: &[ExplosionEvent],
+    ) -> ChainNode {
+        let children = events
+            .iter()
+            .filter_map(|event| match event {
+                ExplosionEvent::ChainTriggered {
+                    from,
+                    to,
+                    direction,
+                    distance,
+                } if *from == point => Some(ChainNode::build(
+                    map,
+                    *to,
+                    Some((*direction, *distance)),
+                    events,
+                )),
+                _ => None,
+            })
+            .collect();
+
+        ChainNode {
+            point,
+            bomb: map.at(&point).clone(),
+            trigger,
+            children,
+        }
+    }
+
+    /// Devuelve la profundidad del arbol, donde una bomba que no detona a ninguna otra tiene profundidad 1.
+    pub fn depth(&self) -> usize {
+        1 + self
+            .children
+            .iter()
+            .map(ChainNode::depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Devuelve la cantidad de bombas del arbol, incluyendo la inicial.
+    pub fn bomb_count(&self) -> usize {
+        1 + self
+            .children
+            .iter()
+            .map(ChainNode::bomb_count)
+            .sum::<usize>()
+    }
+
+    /// Exporta el arbol en formato DOT de Graphviz. Cada nodo muestra la bomba y su posicion,
+    /// y cada arista la direccion y la distancia con la que la rafaga alcanzo a la bomba.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph chain {\n");
+        self.write_dot(&mut dot);
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn write_dot(&self, dot: &mut String) {
+        dot.push_str(&format!(
+            "    \"{},{}\" [label=\"{} ({}, {})\"];\n",
+            self.point.x, self.point.y, self.bomb, self.point.x, self.point.y
+        ));
+        for child in &self.children {
+            if let Some((direction, distance)) = child.trigger {
+                dot.push_str(&format!(
+                    "    \"{},{}\" -> \"{},{}\" [label=\"{:?} {}\"];\n",
+                    self.point.x, self.point.y, child.point.x, child.point.y, direction, distance
+                ));
+            }
+            child.write_dot(dot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_simulate() -> Result<(), Error> {
+        let map = Map::new("test_dir/map.txt")?;
+        let chain = ChainNode::simulate(&map, &Point::new(0, 0))?;
+
+        assert_eq!(chain.point, Point::new(0, 0));
+        assert_eq!(chain.bomb, Item::Bomb(2));
+        assert_eq!(chain.trigger, None);
+        assert_eq!(chain.depth(), 3);
+        assert_eq!(chain.bomb_count(), 3);
+
+        let second = &chain.children[0];
+        assert_eq!(second.point, Point::new(0, 2));
+        assert_eq!(second.trigger, Some((Direction::Down, 2)));
+        assert_eq!(second.children[0].point, Point::new(4, 2));
+        assert_eq!(second.children[0].trigger, Some((Direction::Right, 4)));
+
+        assert_eq!(map.at(&Point::new(0, 0)), &Item::Bomb(2));
+        assert!(ChainNode::simulate(&map, &Point::new(1, 1)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_chain_to_dot() -> Result<(), Error> {
+        let map: Map = "B1 B1\n_ _\n".parse()?;
+        let chain = ChainNode::simulate(&map, &Point::new(0, 0))?;
+
+        assert_eq!(
+            chain.to_dot(),
+            "digraph chain {
+    \"0,0\" [label=\"B1 (0, 0)\"];
+    \"0,0\" -> \"1,0\" [label=\"Right 1\"];
+    \"1,0\" [label=\"B1 (1, 0)\"];
+}
+"
+        );
+
+        Ok(())
+    }
+}
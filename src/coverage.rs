@@ -0,0 +1,110 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{map::Map, map_elements::Item, point::Point};
+
+/// Mapa de calor con la cantidad de bombas cuya explosion alcanza cada celda.
+/// # Attributes
+/// * `areas` - Celdas alcanzadas por cada bomba del mapa, en el orden en que aparecen las bombas.
+/// * `counts` - Cantidad de bombas que alcanzan cada celda, indexada por fila y columna.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heatmap {
+    pub areas: Vec<(Point, HashSet<Point>)>,
+    counts: Vec<Vec<usize>>,
+}
+
+impl Heatmap {
+    /// Calcula el mapa de calor de todas las bombas del mapa, sin modificarlo.
+    /// Cada bomba se considera por separado, sin tener en cuenta las reacciones en cadena.
+    /// # Arguments
+    /// * `map` - Mapa a analizar
+    pub fn from_map(map: &Map) -> Heatmap {
+        let mut counts = vec![vec![0; map.width()]; map.height()];
+        let areas: Vec<(Point, HashSet<Point>)> = map
+            .bombs()
+            .into_iter()
+            .filter_map(|bomb| map.blast_area(&bomb).ok().map(|area| (bomb, area)))
+            .collect();
+
+        for (_, area) in &areas {
+            for point in area {
+                counts[point.y][point.x] += 1;
+            }
+        }
+        Heatmap { areas, counts }
+    }
+
+    /// Devuelve la cantidad de bombas que alcanzan un punto
+    /// # Arguments
+    /// * `point` - Punto a consultar, debe estar dentro del mapa
+    pub fn count(&self, point: &Point) -> usize {
+        self.counts[point.y][point.x]
+    }
+
+    /// Devuelve los puntos que ninguna bomba alcanza, recorriendo el mapa por filas
+    pub fn unreachable(&self) -> Vec<Point> {
+        self.counts
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, count)| **count == 0)
+                    .map(move |(x, _)| Point::new(x, y))
+            })
+            .collect()
+    }
+
+    /// Devuelve los enemigos del mapa que ninguna bomba puede alcanzar
+    /// # Arguments
+    /// * `map` - Mapa con el que se calculo el mapa de calor
+    pub fn unreachable_enemies(&self, map: &Map) -> Vec<Point> {
+        self.unreachable()
+            .into_iter()
+            .filter(|point| matches!(map.at(point), Item::Enemy(_)))
+            .collect()
+    }
+}
+
+impl fmt::Display for Heatmap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cell_width = self
+            .counts
+            .iter()
+            .flatten()
+            .map(|count| count.to_string().len())
+            .max()
+            .unwrap_or(1);
+
+        for row in &self.counts {
+            let line: Vec<String> = row
+                .iter()
+                .map(|count| format!("{:>width$}", count, width = cell_width))
+                .collect();
+            writeln!(f, "{}", line.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn test_heatmap() -> Result<(), Error> {
+        let map: Map = "B1 _ F1\n_ B2 W\nF2 W F1\n".parse()?;
+        let heatmap = Heatmap::from_map(&map);
+
+        assert_eq!(heatmap.to_string(), "0 2 0\n2 0 0\n0 0 0\n");
+        assert_eq!(heatmap.count(&Point::new(1, 0)), 2);
+        assert_eq!(heatmap.areas.len(), 2);
+        assert_eq!(
+            heatmap.unreachable_enemies(&map),
+            vec![Point::new(2, 0), Point::new(0, 2), Point::new(2, 2)]
+        );
+        assert_eq!(map.at(&Point::new(0, 0)), &Item::Bomb(1));
+
+        Ok(())
+    }
+}
@@ -1,8 +1,8 @@
 use std::fmt;
 
-use crate::direction::Direction;
+use crate::{direction::Direction, error::Error};
 
-/// Los desvios se implementan como direcciones, ya que solo se puede desviar arriba, abajo, izquierda o derecha, igual que las direcciones.
+/// Los desvios se implementan como direcciones, ya que se puede desviar en las mismas 4 direcciones ortogonales y 4 diagonales en las que se propagan las explosiones.
 /// Esto faicilita la implementacion de los desvios ya que se puede usar la misma logica que para las direcciones al propagar explosiones.
 pub type Detour = Direction;
 
@@ -13,6 +13,10 @@ impl fmt::Display for Detour {
             Detour::Down => write!(f, "DD"),
             Detour::Left => write!(f, "DL"),
             Detour::Right => write!(f, "DR"),
+            Detour::UpLeft => write!(f, "DUL"),
+            Detour::UpRight => write!(f, "DUR"),
+            Detour::DownLeft => write!(f, "DDL"),
+            Detour::DownRight => write!(f, "DDR"),
         }
     }
 }
@@ -22,17 +26,19 @@ impl Detour {
     /// # Arguments
     /// * `string` - String a parsear.
     /// # Returns
-    /// * `Result<Detour, String>` - Desvio parseado o mensaje de error.
-    pub fn parse(string: &str) -> Result<Detour, String> {
+    /// * `Result<Detour, Error>` - Desvio parseado o error.
+    pub fn parse(string: &str) -> Result<Detour, Error> {
         let (_, direction) = string.split_at(1);
         match direction {
             "U" => Ok(Detour::Up),
             "D" => Ok(Detour::Down),
             "L" => Ok(Detour::Left),
             "R" => Ok(Detour::Right),
-            _ => Err(String::from(
-                "ERROR: [El archivo de entrada contiene una direccion de desvio invalida].",
-            )),
+            "UL" => Ok(Detour::UpLeft),
+            "UR" => Ok(Detour::UpRight),
+            "DL" => Ok(Detour::DownLeft),
+            "DR" => Ok(Detour::DownRight),
+            _ => Err(Error::InvalidDetour),
         }
     }
 }
@@ -47,6 +53,10 @@ mod tests {
         assert_eq!(format!("{}", Detour::Down), "DD");
         assert_eq!(format!("{}", Detour::Left), "DL");
         assert_eq!(format!("{}", Detour::Right), "DR");
+        assert_eq!(format!("{}", Detour::UpLeft), "DUL");
+        assert_eq!(format!("{}", Detour::UpRight), "DUR");
+        assert_eq!(format!("{}", Detour::DownLeft), "DDL");
+        assert_eq!(format!("{}", Detour::DownRight), "DDR");
     }
 
     #[test]
@@ -55,9 +65,14 @@ mod tests {
         assert_eq!(Detour::parse("DD").unwrap(), Detour::Down);
         assert_eq!(Detour::parse("DL").unwrap(), Detour::Left);
         assert_eq!(Detour::parse("DR").unwrap(), Detour::Right);
+        assert_eq!(Detour::parse("DUL").unwrap(), Detour::UpLeft);
+        assert_eq!(Detour::parse("DUR").unwrap(), Detour::UpRight);
+        assert_eq!(Detour::parse("DDL").unwrap(), Detour::DownLeft);
+        assert_eq!(Detour::parse("DDR").unwrap(), Detour::DownRight);
+        assert!(Detour::parse("DLU").is_err());
 
         let err = match Detour::parse("DZ") {
-            Err(e) => e,
+            Err(e) => e.to_string(),
             _ => String::from(""),
         };
 
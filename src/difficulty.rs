@@ -0,0 +1,167 @@
+use std::{collections::HashSet, fmt};
+
+use crate::{
+    chain::ChainNode,
+    explosion::ExplosionEvent,
+    game,
+    map::Map,
+    point::Point,
+    solve::{self, CountOutcome, SearchBudget, SolveOutcome},
+};
+
+/// Metricas con las que se estima la dificultad de un mapa.
+/// # Attributes
+/// * `min_solution_length` - Largo de la solucion mas corta, o None si el mapa no tiene solucion o no se encontro dentro del presupuesto.
+/// * `solutions` - Cantidad de soluciones distintas de largo minimo, o None si no hay solucion o se agoto el presupuesto al contarlas.
+/// * `chain_depth` - Profundidad maxima de las reacciones en cadena que produce detonar una bomba del mapa inicial.
+/// * `detours_used` - Cantidad de desvios distintos que redirigen alguna explosion del mapa inicial.
+/// * `dead_end_fraction` - Fraccion de las primeras jugadas luego de las cuales el mapa ya no tiene solucion.
+/// * `score` - Puntaje de dificultad, mayor cuanto mas dificil. Es None si no se encontro una solucion o no se pudieron contar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difficulty {
+    pub min_solution_length: Option<usize>,
+    pub solutions: Option<usize>,
+    pub chain_depth: usize,
+    pub detours_used: usize,
+    pub dead_end_fraction: f64,
+    pub score: Option<f64>,
+}
+
+impl Difficulty {
+    /// Analiza un mapa y calcula su dificultad, sin modificarlo.
+    /// # Arguments
+    /// * `map` - Mapa a analizar
+    /// * `budget` - Limites de cada busqueda de soluciones
+    pub fn rate(map: &Map, budget: &SearchBudget) -> Difficulty {
+        let first_moves = map.bombs();
+        let min_solution_length = match solve::solve_sequence(map, budget) {
+            SolveOutcome::Solved(sequence) => Some(sequence.len()),
+            _ => None,
+        };
+        let solutions = min_solution_length.and_then(|length| {
+            match solve::count_solutions(map, length, budget) {
+                CountOutcome::Counted(count) => Some(count),
+                CountOutcome::BudgetExceeded { .. } => None,
+            }
+        });
+
+        let mut chain_depth = 0;
+        let mut detours: HashSet<Point> = HashSet::new();
+        for bomb in &first_moves {
+            let mut simulated = map.clone();
+            if let Ok(events) = simulated.detonate_bomb_traced(bomb) {
+                let chain = ChainNode::from_events(map, bomb, &events);
+                chain_depth = chain_depth.max(chain.depth());
+                detours.extend(events.iter().filter_map(|event| match event {
+                    ExplosionEvent::Redirected { point, .. } => Some(*point),
+                    _ => None,
+                }));
+            }
+        }
+
+        let dead_ends = first_moves
+            .iter()
+            .filter(|bomb| {
+                let mut next = map.clone();
+                game::execute_turn(&mut next, **bomb).is_ok()
+                    && matches!(
+                        solve::solve_sequence(&next, budget),
+                        SolveOutcome::Unsolvable { .. }
+                    )
+            })
+            .count();
+        let dead_end_fraction = if first_moves.is_empty() {
+            0.0
+        } else {
+            dead_ends as f64 / first_moves.len() as f64
+        };
+
+        let score = min_solution_length
+            .zip(solutions)
+            .map(|(length, solutions)| {
+                2.0 * length as f64
+                    + 3.0 / solutions.max(1) as f64
+                    + chain_depth.saturating_sub(1) as f64
+                    + 0.5 * detours.len() as f64
+                    + 5.0 * dead_end_fraction
+            });
+
+        Difficulty {
+            min_solution_length,
+            solutions,
+            chain_depth,
+            detours_used: detours.len(),
+            dead_end_fraction,
+            score,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.score {
+            Some(score) => writeln!(f, "Dificultad: {:.2}", score)?,
+            None if self.min_solution_length.is_some() => {
+                writeln!(f, "Dificultad: se agoto el presupuesto de busqueda")?
+            }
+            None => writeln!(f, "Dificultad: sin solucion")?,
+        }
+        match self.min_solution_length {
+            Some(length) => writeln!(f, "  solucion mas corta: {} turnos", length)?,
+            None => writeln!(f, "  solucion mas corta: -")?,
+        }
+        match self.solutions {
+            Some(solutions) => writeln!(f, "  soluciones minimas distintas: {}", solutions)?,
+            None => writeln!(f, "  soluciones minimas distintas: -")?,
+        }
+        writeln!(
+            f,
+            "  profundidad de reaccion en cadena: {}",
+            self.chain_depth
+        )?;
+        writeln!(f, "  desvios utilizados: {}", self.detours_used)?;
+        writeln!(
+            f,
+            "  primeras jugadas sin salida: {:.0}%",
+            self.dead_end_fraction * 100.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[test]
+    fn test_rate() -> Result<(), Error> {
+        let map: Map = "B1 F2 B1\n_ _ _\nF1 _ B2\n".parse()?;
+        let difficulty = Difficulty::rate(&map, &SearchBudget::default());
+
+        assert_eq!(difficulty.min_solution_length, Some(2));
+        assert_eq!(difficulty.solutions, Some(2));
+        assert_eq!(difficulty.chain_depth, 2);
+        assert_eq!(difficulty.detours_used, 0);
+        assert_eq!(difficulty.dead_end_fraction, 0.0);
+        assert_eq!(difficulty.score, Some(6.5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rate_unsolvable() -> Result<(), Error> {
+        let map: Map = "B1 DD F1\nW _ _\n".parse()?;
+        let difficulty = Difficulty::rate(&map, &SearchBudget::default());
+
+        assert_eq!(difficulty.min_solution_length, None);
+        assert_eq!(difficulty.solutions, None);
+        assert_eq!(difficulty.detours_used, 1);
+        assert_eq!(difficulty.dead_end_fraction, 1.0);
+        assert_eq!(difficulty.score, None);
+        assert!(difficulty
+            .to_string()
+            .starts_with("Dificultad: sin solucion\n"));
+
+        Ok(())
+    }
+}
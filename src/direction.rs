@@ -1,8 +1,89 @@
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub enum Direction {
-    /// Las 4 direcciones posibles en 2 dimensiones
+    /// Las 4 direcciones ortogonales y las 4 diagonales posibles en 2 dimensiones
     Up,
     Down,
     Left,
     Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    /// Las 4 direcciones ortogonales, en el mismo orden en que se propagan las explosiones.
+    pub const ORTHOGONAL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+    ];
+
+    /// Las 4 direcciones diagonales, en el mismo orden en que se propagan las explosiones diagonales.
+    pub const DIAGONAL: [Direction; 4] = [
+        Direction::UpLeft,
+        Direction::UpRight,
+        Direction::DownLeft,
+        Direction::DownRight,
+    ];
+
+    /// Devuelve la direccion contraria.
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::UpLeft => Direction::DownRight,
+            Direction::UpRight => Direction::DownLeft,
+            Direction::DownLeft => Direction::UpRight,
+            Direction::DownRight => Direction::UpLeft,
+        }
+    }
+
+    /// Devuelve la direccion correspondiente a un desplazamiento de un paso, o None si no corresponde a ninguna.
+    /// # Arguments
+    /// * `offset` - Desplazamiento en x e y, cada uno entre -1 y 1.
+    pub fn from_offset(offset: (isize, isize)) -> Option<Direction> {
+        Direction::ORTHOGONAL
+            .into_iter()
+            .chain(Direction::DIAGONAL)
+            .find(|direction| direction.offset() == offset)
+    }
+
+    /// Devuelve el desplazamiento en x e y de un paso en la direccion.
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+            Direction::Right => (1, 0),
+            Direction::UpLeft => (-1, -1),
+            Direction::UpRight => (1, -1),
+            Direction::DownLeft => (-1, 1),
+            Direction::DownRight => (1, 1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction_opposite() {
+        for direction in Direction::ORTHOGONAL.into_iter().chain(Direction::DIAGONAL) {
+            assert_ne!(direction.opposite(), direction);
+            assert_eq!(direction.opposite().opposite(), direction);
+
+            let (x, y) = direction.offset();
+            assert_eq!(direction.opposite().offset(), (-x, -y));
+        }
+        assert_eq!(Direction::Up.opposite(), Direction::Down);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        assert_eq!(Direction::UpLeft.opposite(), Direction::DownRight);
+        assert_eq!(Direction::from_offset((1, -1)), Some(Direction::UpRight));
+        assert_eq!(Direction::from_offset((0, 0)), None);
+    }
 }
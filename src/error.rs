@@ -0,0 +1,309 @@
+use std::{fmt, io};
+
+/// Ubicacion de un token dentro del texto de un mapa.
+/// # Attributes
+/// * `row` - Fila del token, comenzando en 1.
+/// * `column` - Columna del primer caracter del token, comenzando en 1.
+/// * `offset` - Posicion en bytes del token desde el comienzo del texto.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Position {
+    pub row: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+impl Position {
+    /// Crea una nueva posicion.
+    /// # Arguments
+    /// * `row` - Fila del token, comenzando en 1.
+    /// * `column` - Columna del token, comenzando en 1.
+    /// * `offset` - Posicion en bytes del token desde el comienzo del texto.
+    pub fn new(row: usize, column: usize, offset: usize) -> Self {
+        Position {
+            row,
+            column,
+            offset,
+        }
+    }
+}
+
+/// Errores que pueden ocurrir al leer, parsear o jugar un mapa.
+/// Al mostrarse se renderizan con el formato de la catedra "ERROR: [...]." para que la salida del programa no cambie.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Error {
+    /// No se ingreso el directorio de salida.
+    MissingOutputDirectory,
+    /// No se ingresaron todas las coordenadas de la bomba.
+    MissingArguments,
+    /// Las coordenadas de la bomba no son numeros validos.
+    InvalidCoordinates,
+    /// No se pudo leer la entrada, contiene el tipo de error de entrada/salida original.
+    Io(io::ErrorKind),
+    /// El mapa contiene un caracter que no corresponde a ningun item.
+    InvalidToken(String),
+    /// Los puntos de vida o el alcance no son un numero.
+    InvalidNumber,
+    /// Los puntos de vida o el alcance son cero.
+    NonPositiveNumber,
+    /// Un enemigo tiene mas vida de la permitida.
+    EnemyHealthOverLimit { health: u32, max: u32 },
+    /// Un desvio apunta a una direccion inexistente.
+    InvalidDetour,
+    /// Las coordenadas a detonar estan fuera del mapa.
+    CoordinatesOutsideMap,
+    /// Las coordenadas a detonar no contienen una bomba.
+    NotABomb,
+    /// Se intento detonar una bomba inexistente durante la propagacion de una explosion.
+    Execution,
+    /// La entrada no contiene ninguna fila.
+    EmptyMap,
+    /// Una fila del mapa tiene una cantidad de items distinta a la primera fila.
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// Un teletransportador tiene un numero fuera del rango permitido.
+    InvalidTeleporter { id: u32, max: u32 },
+    /// Un teletransportador no tiene exactamente una pareja con su mismo numero.
+    UnpairedTeleporter { id: u32, count: usize },
+    /// Las densidades del generador de mapas no son probabilidades validas o el alcance maximo es cero.
+    InvalidGeneratorConfig,
+    /// El generador no encontro un mapa que pueda resolverse en los turnos pedidos luego de `attempts` intentos.
+    GenerationFailed { attempts: usize },
+    /// La regla de movimiento de los enemigos no existe o tiene parametros invalidos.
+    InvalidMovementRule,
+    /// Una linea del archivo de reglas no tiene el formato `clave = valor`, la clave no existe o el valor es invalido.
+    InvalidRule { line: usize, rule: String },
+    /// Un turno de una partida no pudo jugarse, junto con su numero comenzando en 1.
+    Turn { turn: usize, cause: Box<Error> },
+    /// Error al parsear un token del mapa, junto con el token y su ubicacion en el texto.
+    Parse {
+        cause: Box<Error>,
+        token: String,
+        position: Position,
+    },
+}
+
+impl Error {
+    /// Asocia el error al token que lo produjo y a su ubicacion dentro del mapa.
+    /// # Arguments
+    /// * `token` - Token que produjo el error.
+    /// * `position` - Ubicacion del token en el texto del mapa.
+    pub fn at(self, token: &str, position: Position) -> Error {
+        Error::Parse {
+            cause: Box::new(self),
+            token: token.to_string(),
+            position,
+        }
+    }
+
+    /// Devuelve la ubicacion del token que produjo el error, si se conoce.
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            Error::Parse { position, .. } => Some(*position),
+            _ => None,
+        }
+    }
+
+    /// Devuelve el error original, sin la informacion de ubicacion.
+    pub fn kind(&self) -> &Error {
+        match self {
+            Error::Turn { cause, .. } | Error::Parse { cause, .. } => cause.kind(),
+            error => error,
+        }
+    }
+
+    /// Renderiza el error junto con la linea del mapa que lo produjo, marcando el token con `^`.
+    /// # Arguments
+    /// * `source` - Texto del mapa del que proviene el error.
+    /// # Returns
+    /// * `String` - Mensaje de error, seguido de la linea y el marcador cuando se conoce la ubicacion.
+    pub fn render_snippet(&self, source: &str) -> String {
+        let (token, position) = match self {
+            Error::Parse {
+                token, position, ..
+            } => (token, position),
+            error => return error.to_string(),
+        };
+        let line = source
+            .split('\n')
+            .nth(position.row - 1)
+            .unwrap_or("")
+            .trim_end_matches('\r');
+        let gutter = " ".repeat(position.row.to_string().len());
+
+        format!(
+            "{}\n{} --> fila {}, columna {}\n{} |\n{} | {}\n{} | {}{}",
+            self,
+            gutter,
+            position.row,
+            position.column,
+            gutter,
+            position.row,
+            line,
+            gutter,
+            " ".repeat(position.column - 1),
+            "^".repeat(token.chars().count().max(1))
+        )
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingOutputDirectory => {
+                write!(f, "ERROR: [No se ingreso un directorio de output].")
+            }
+            Error::MissingArguments => write!(f, "ERROR: [No se ingresaron todos los argumentos]."),
+            Error::InvalidCoordinates => write!(
+                f,
+                "ERROR: [Error al interpretar coordenadas de la bomba, por favor ingrese numeros o intente con un numero mas pequeño]."
+            ),
+            Error::Io(_) => write!(f, "ERROR: [No se pudo leer el archivo de entrada]."),
+            Error::InvalidToken(token) => write!(
+                f,
+                "ERROR: [El archivo de entrada contiene un caracter invalido '{}'].",
+                token
+            ),
+            Error::InvalidNumber => write!(
+                f,
+                "ERROR: [Error al interpretar puntos de vida/alcance en el archivo de entrada]."
+            ),
+            Error::NonPositiveNumber => write!(
+                f,
+                "ERROR: [El archivo de entrada contiene un numero menor o igual a cero]."
+            ),
+            Error::EnemyHealthOverLimit { max, .. } => write!(
+                f,
+                "ERROR: [La vida de los enemigos no puede ser mayor a {}].",
+                max
+            ),
+            Error::InvalidDetour => write!(
+                f,
+                "ERROR: [El archivo de entrada contiene una direccion de desvio invalida]."
+            ),
+            Error::CoordinatesOutsideMap => write!(
+                f,
+                "ERROR: [Las coordenadas a detonar no se encuentran en el mapa]."
+            ),
+            Error::NotABomb => write!(
+                f,
+                "ERROR: [Las coordenadas ingresadas no corresponden a ninguna bomba]."
+            ),
+            Error::Execution => write!(f, "ERROR: [Ocurrio un error durante la ejecucion]."),
+            Error::EmptyMap => write!(f, "ERROR: [El archivo de entrada no contiene ningun mapa]."),
+            Error::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "ERROR: [La fila {} del archivo de entrada contiene {} elementos, se esperaban {}].",
+                row, found, expected
+            ),
+            Error::InvalidTeleporter { max, .. } => write!(
+                f,
+                "ERROR: [Los teletransportadores deben numerarse del 1 al {}].",
+                max
+            ),
+            Error::UnpairedTeleporter { id, count } => write!(
+                f,
+                "ERROR: [El teletransportador P{} aparece {} veces, debe aparecer exactamente 2].",
+                id, count
+            ),
+            Error::InvalidGeneratorConfig => write!(
+                f,
+                "ERROR: [La configuracion del generador de mapas es invalida]."
+            ),
+            Error::GenerationFailed { attempts } => write!(
+                f,
+                "ERROR: [No se pudo generar un mapa con solucion luego de {} intentos].",
+                attempts
+            ),
+            Error::InvalidMovementRule => write!(
+                f,
+                "ERROR: [La regla de movimiento de los enemigos es invalida]."
+            ),
+            Error::InvalidRule { line, rule } => write!(
+                f,
+                "ERROR: [La regla '{}' de la linea {} del archivo de reglas es invalida].",
+                rule, line
+            ),
+            Error::Turn { cause, .. } | Error::Parse { cause, .. } => write!(f, "{}", cause),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Turn { cause, .. } | Error::Parse { cause, .. } => Some(cause.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Error::Io(error.kind())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            Error::InvalidToken(String::from("H")).to_string(),
+            "ERROR: [El archivo de entrada contiene un caracter invalido 'H']."
+        );
+        assert_eq!(
+            Error::EnemyHealthOverLimit { health: 4, max: 3 }.to_string(),
+            "ERROR: [La vida de los enemigos no puede ser mayor a 3]."
+        );
+        assert_eq!(
+            Error::Io(io::ErrorKind::NotFound).to_string(),
+            "ERROR: [No se pudo leer el archivo de entrada]."
+        );
+    }
+
+    #[test]
+    fn test_error_at_position() {
+        let error = Error::InvalidToken(String::from("H")).at("H", Position::new(2, 3, 7));
+
+        assert_eq!(
+            error.to_string(),
+            "ERROR: [El archivo de entrada contiene un caracter invalido 'H']."
+        );
+        assert_eq!(error.position(), Some(Position::new(2, 3, 7)));
+        assert_eq!(error.kind(), &Error::InvalidToken(String::from("H")));
+        assert_eq!(Error::NotABomb.position(), None);
+    }
+
+    #[test]
+    fn test_error_render_snippet() {
+        let error = Error::InvalidNumber.at("Fb", Position::new(2, 3, 7));
+
+        assert_eq!(
+            error.render_snippet("_ F1\nR Fb _\n"),
+            "ERROR: [Error al interpretar puntos de vida/alcance en el archivo de entrada].
+  --> fila 2, columna 3
+  |
+2 | R Fb _
+  |   ^^"
+        );
+        assert_eq!(
+            Error::NotABomb.render_snippet(""),
+            Error::NotABomb.to_string()
+        );
+    }
+
+    #[test]
+    fn test_error_from_io() {
+        let error: Error = io::Error::new(io::ErrorKind::NotFound, "no existe").into();
+        assert_eq!(error, Error::Io(io::ErrorKind::NotFound));
+    }
+}
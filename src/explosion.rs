@@ -0,0 +1,179 @@
+use std::fmt;
+
+use crate::{direction::Direction, point::Point};
+
+/// Eventos que ocurren al detonar una bomba, registrados en el orden en que suceden.
+/// Permiten reconstruir el recorrido de la explosion sin tener que comparar el mapa antes y despues.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ExplosionEvent {
+    /// Una bomba exploto en `point`, con su alcance y si es de traspaso.
+    BombDetonated {
+        point: Point,
+        reach: u32,
+        piercing: bool,
+    },
+    /// La rafaga alcanzo la celda `point`.
+    CellBurned { point: Point },
+    /// Un desvio en `point` cambio la direccion de la rafaga a `direction`.
+    Redirected { point: Point, direction: Direction },
+    /// Un espejo en `point` reflejo la rafaga hacia `direction`.
+    Reflected { point: Point, direction: Direction },
+    /// Un divisor en `point` separo la rafaga en las direcciones `first` y `second`.
+    Split {
+        point: Point,
+        first: Direction,
+        second: Direction,
+    },
+    /// La rafaga volvio a `point` con la direccion `direction` que ya habia tenido ahi, por lo que se detuvo para no repetir el ciclo.
+    LoopDetected { point: Point, direction: Direction },
+    /// La rafaga entro al teletransportador en `from` y continua desde su pareja en `to`.
+    Teleported { from: Point, to: Point },
+    /// La rafaga se detuvo contra una pared en `point`.
+    BlockedByWall { point: Point },
+    /// La rafaga de una bomba normal se detuvo contra una roca en `point`.
+    BlockedByRock { point: Point },
+    /// El enemigo en `point` recibio daño y le queda `health` de vida.
+    EnemyDamaged { point: Point, health: u32 },
+    /// El enemigo en `point` murio.
+    EnemyKilled { point: Point },
+    /// La roca en `point` recibio daño y le queda `health` de vida.
+    RockDamaged { point: Point, health: u32 },
+    /// La roca en `point` fue destruida.
+    RockDestroyed { point: Point },
+    /// Se agoto el temporizador de la bomba en `point`, que explota a continuacion.
+    FuseExpired { point: Point },
+    /// La rafaga de la bomba en `from`, lanzada en `direction`, alcanzo a `distance` celdas la bomba en `to` y la detono.
+    ChainTriggered {
+        from: Point,
+        to: Point,
+        direction: Direction,
+        distance: u32,
+    },
+}
+
+impl fmt::Display for ExplosionEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExplosionEvent::BombDetonated {
+                point,
+                reach,
+                piercing,
+            } => write!(
+                f,
+                "BombDetonated x={} y={} reach={} piercing={}",
+                point.x, point.y, reach, piercing
+            ),
+            ExplosionEvent::CellBurned { point } => {
+                write!(f, "CellBurned x={} y={}", point.x, point.y)
+            }
+            ExplosionEvent::Redirected { point, direction } => write!(
+                f,
+                "Redirected x={} y={} direction={:?}",
+                point.x, point.y, direction
+            ),
+            ExplosionEvent::Reflected { point, direction } => write!(
+                f,
+                "Reflected x={} y={} direction={:?}",
+                point.x, point.y, direction
+            ),
+            ExplosionEvent::Split {
+                point,
+                first,
+                second,
+            } => write!(
+                f,
+                "Split x={} y={} first={:?} second={:?}",
+                point.x, point.y, first, second
+            ),
+            ExplosionEvent::LoopDetected { point, direction } => write!(
+                f,
+                "LoopDetected x={} y={} direction={:?}",
+                point.x, point.y, direction
+            ),
+            ExplosionEvent::Teleported { from, to } => write!(
+                f,
+                "Teleported from_x={} from_y={} to_x={} to_y={}",
+                from.x, from.y, to.x, to.y
+            ),
+            ExplosionEvent::BlockedByWall { point } => {
+                write!(f, "BlockedByWall x={} y={}", point.x, point.y)
+            }
+            ExplosionEvent::BlockedByRock { point } => {
+                write!(f, "BlockedByRock x={} y={}", point.x, point.y)
+            }
+            ExplosionEvent::EnemyDamaged { point, health } => write!(
+                f,
+                "EnemyDamaged x={} y={} health={}",
+                point.x, point.y, health
+            ),
+            ExplosionEvent::EnemyKilled { point } => {
+                write!(f, "EnemyKilled x={} y={}", point.x, point.y)
+            }
+            ExplosionEvent::RockDamaged { point, health } => write!(
+                f,
+                "RockDamaged x={} y={} health={}",
+                point.x, point.y, health
+            ),
+            ExplosionEvent::RockDestroyed { point } => {
+                write!(f, "RockDestroyed x={} y={}", point.x, point.y)
+            }
+            ExplosionEvent::FuseExpired { point } => {
+                write!(f, "FuseExpired x={} y={}", point.x, point.y)
+            }
+            ExplosionEvent::ChainTriggered {
+                from,
+                to,
+                direction,
+                distance,
+            } => write!(
+                f,
+                "ChainTriggered from_x={} from_y={} to_x={} to_y={} direction={:?} distance={}",
+                from.x, from.y, to.x, to.y, direction, distance
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explosion_event_display() {
+        assert_eq!(
+            ExplosionEvent::BombDetonated {
+                point: Point::new(1, 2),
+                reach: 3,
+                piercing: false
+            }
+            .to_string(),
+            "BombDetonated x=1 y=2 reach=3 piercing=false"
+        );
+        assert_eq!(
+            ExplosionEvent::Redirected {
+                point: Point::new(0, 4),
+                direction: Direction::Up
+            }
+            .to_string(),
+            "Redirected x=0 y=4 direction=Up"
+        );
+        assert_eq!(
+            ExplosionEvent::ChainTriggered {
+                from: Point::new(0, 0),
+                to: Point::new(0, 2),
+                direction: Direction::Down,
+                distance: 2
+            }
+            .to_string(),
+            "ChainTriggered from_x=0 from_y=0 to_x=0 to_y=2 direction=Down distance=2"
+        );
+        assert_eq!(
+            ExplosionEvent::LoopDetected {
+                point: Point::new(1, 0),
+                direction: Direction::Left
+            }
+            .to_string(),
+            "LoopDetected x=1 y=0 direction=Left"
+        );
+    }
+}
@@ -1,4 +1,38 @@
-use crate::{io, map, map_elements::Item, point::Point};
+use crate::{
+    error::Error,
+    explosion::ExplosionEvent,
+    io, map,
+    movement::{EnemyMove, EnemyMovement, MovementRule},
+    point::Point,
+};
+
+/// Quita una opcion sin valor de los argumentos de entrada, por ejemplo `--trace`
+/// # Arguments
+/// * `args` - Vector de strings con los argumentos de entrada
+/// * `flag` - Opcion a buscar
+/// # Returns
+/// * `bool` - True si la opcion se encontraba entre los argumentos
+pub fn take_flag(args: &mut Vec<String>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != flag);
+    args.len() != len
+}
+
+/// Quita una opcion con valor de los argumentos de entrada, por ejemplo `--dot archivo.dot`
+/// # Arguments
+/// * `args` - Vector de strings con los argumentos de entrada
+/// * `option` - Opcion a buscar
+/// # Returns
+/// * `Option<String>` - Valor de la opcion, o None si la opcion no se encontraba o no tenia valor
+pub fn take_option(args: &mut Vec<String>, option: &str) -> Option<String> {
+    let index = args.iter().position(|arg| arg == option)?;
+    args.remove(index);
+    if index < args.len() {
+        Some(args.remove(index))
+    } else {
+        None
+    }
+}
 
 /// Toma los argumentos de entrada y valida que sean correctos, en caso de serlo devuelve una tupla con los argumentos
 /// # Arguments
@@ -6,14 +40,30 @@ use crate::{io, map, map_elements::Item, point::Point};
 /// # Returns
 /// * `Option<(String, String, usize, usize)>` - Tupla con los argumentos de entrada
 pub fn validate_input(args: Vec<String>) -> Option<(String, String, usize, usize)> {
+    let (input_file, output_file, points) = validate_turns_input(args, None)?;
+    Some((input_file, output_file, points[0].x, points[0].y))
+}
+
+/// Toma los argumentos de entrada de una partida de varios turnos y valida que sean correctos.
+/// Las coordenadas se leen de a pares a partir del cuarto argumento, o del archivo de jugadas en caso de indicarse.
+/// Un ultimo argumento sin pareja se ignora, igual que el comando original de un solo turno ignoraba los argumentos de mas.
+/// # Arguments
+/// * `args` - Vector de strings con los argumentos de entrada
+/// * `moves_file` - Archivo de jugadas opcional, con un par de coordenadas `x y` por linea
+/// # Returns
+/// * `Option<(String, String, Vec<Point>)>` - Tupla con el archivo de entrada, el de salida y los puntos a detonar en orden
+pub fn validate_turns_input(
+    args: Vec<String>,
+    moves_file: Option<&str>,
+) -> Option<(String, String, Vec<Point>)> {
     if args.len() <= 2 {
-        println!("ERROR: [No se ingreso un directorio de output].");
+        println!("{}", Error::MissingOutputDirectory);
         return None;
-    } else if args.len() <= 4 {
+    } else if args.len() <= 4 && moves_file.is_none() {
         io::create_directory(&args[2]);
         io::write_error(
             &(args[2].to_string() + "/" + &args[1].to_string()),
-            "ERROR: [No se ingresaron todos los argumentos].",
+            &Error::MissingArguments.to_string(),
         );
         return None;
     }
@@ -26,48 +76,235 @@ pub fn validate_input(args: Vec<String>) -> Option<(String, String, usize, usize
     println!("in file: {}", input_file);
     println!("out file: {}", output_file);
 
-    let (x, y) = match (args[3].parse::<usize>(), args[4].parse::<usize>()) {
-        (Ok(x), Ok(y)) => (x, y),
-        _ => {
-            io::write_error(
-                &output_file,
-                "ERROR: [Error al interpretar coordenadas de la bomba, por favor ingrese numeros o intente con un numero mas pequeño].",
-            );
-            return None;
+    let points = match moves_file {
+        Some(moves_file) => read_moves(moves_file),
+        None => {
+            let coordinates = &args[3..];
+            parse_coordinates(&coordinates[..coordinates.len() - coordinates.len() % 2])
         }
     };
 
-    Some((input_file, output_file, x, y))
+    match points {
+        Ok(points) => Some((input_file, output_file, points)),
+        Err(e) => {
+            io::write_error(&output_file, &e.to_string());
+            None
+        }
+    }
+}
+
+/// Parsea una lista de coordenadas tomadas de a pares `x y`
+/// # Arguments
+/// * `values` - Coordenadas a parsear
+/// # Returns
+/// * `Result<Vec<Point>, Error>` - Puntos parseados en orden, o error si falta una coordenada o alguna no es un numero
+pub fn parse_coordinates(values: &[String]) -> Result<Vec<Point>, Error> {
+    let pairs = values.chunks_exact(2);
+    if values.is_empty() || !pairs.remainder().is_empty() {
+        return Err(Error::MissingArguments);
+    }
+
+    pairs
+        .map(
+            |pair| match (pair[0].parse::<usize>(), pair[1].parse::<usize>()) {
+                (Ok(x), Ok(y)) => Ok(Point::new(x, y)),
+                _ => Err(Error::InvalidCoordinates),
+            },
+        )
+        .collect()
+}
+
+/// Lee un archivo de jugadas, con un par de coordenadas `x y` por linea. Se ignoran las lineas en blanco y las que comienzan con `#`.
+/// # Arguments
+/// * `moves_file` - Nombre del archivo de jugadas
+/// # Returns
+/// * `Result<Vec<Point>, Error>` - Puntos a detonar en orden, o error si el archivo no puede leerse o contiene coordenadas invalidas
+pub fn read_moves(moves_file: &str) -> Result<Vec<Point>, Error> {
+    let moves_raw = io::read_file(moves_file)?;
+    let mut points = Vec::new();
+
+    for line in moves_raw.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let values: Vec<String> = line.split_whitespace().map(String::from).collect();
+        if values.len() != 2 {
+            return Err(Error::InvalidCoordinates);
+        }
+        points.extend(parse_coordinates(&values)?);
+    }
+
+    if points.is_empty() {
+        return Err(Error::MissingArguments);
+    }
+    Ok(points)
+}
+
+/// Partida de varios turnos sobre un mapa. En cada turno se detona una bomba, validando que el punto sea valido en el estado actual del mapa,
+/// y luego se mueven los enemigos segun la regla de movimiento de la partida.
+#[derive(Debug, Clone)]
+pub struct Game {
+    map: map::Map,
+    turn: usize,
+    movement: EnemyMovement,
+    last_moves: Vec<EnemyMove>,
+}
+
+impl Game {
+    /// Crea una partida a partir del mapa inicial, con enemigos que no se mueven
+    /// # Arguments
+    /// * `map` - Mapa inicial de la partida
+    pub fn new(map: map::Map) -> Self {
+        Game::with_movement(map, MovementRule::Static)
+    }
+
+    /// Crea una partida a partir del mapa inicial, moviendo a los enemigos luego de cada turno
+    /// # Arguments
+    /// * `map` - Mapa inicial de la partida
+    /// * `rule` - Regla de movimiento de los enemigos
+    pub fn with_movement(map: map::Map, rule: MovementRule) -> Self {
+        Game {
+            map,
+            turn: 0,
+            movement: EnemyMovement::new(rule),
+            last_moves: Vec::new(),
+        }
+    }
+
+    /// Devuelve los movimientos de los enemigos en el ultimo turno jugado
+    pub fn last_moves(&self) -> &[EnemyMove] {
+        &self.last_moves
+    }
+
+    /// Devuelve el estado actual del mapa
+    pub fn map(&self) -> &map::Map {
+        &self.map
+    }
+
+    /// Devuelve la cantidad de turnos jugados
+    pub fn turn(&self) -> usize {
+        self.turn
+    }
+
+    /// Consume la partida y devuelve el mapa en su estado actual
+    pub fn into_map(self) -> map::Map {
+        self.map
+    }
+
+    /// Juega un turno detonando la bomba en el punto ingresado y luego mueve a los enemigos
+    /// # Arguments
+    /// * `point` - Punto donde se detonara la bomba
+    /// # Returns
+    /// * `Result<Vec<ExplosionEvent>, Error>` - Eventos de la explosion, o el error en caso de que el punto no sea valido. En ese caso el turno no se cuenta y el mapa no se modifica
+    pub fn play_turn(&mut self, point: Point) -> Result<Vec<ExplosionEvent>, Error> {
+        Ok(self.play_recorded_turn(point)?.events)
+    }
+
+    /// Juega un turno como `play_turn`, registrando cada celda que modificaron las explosiones y el movimiento de los enemigos
+    /// # Arguments
+    /// * `point` - Punto donde se detonara la bomba
+    /// # Returns
+    /// * `Result<map::Detonation, Error>` - Registro del turno completo, o el error en caso de que el punto no sea valido
+    pub fn play_recorded_turn(&mut self, point: Point) -> Result<map::Detonation, Error> {
+        let mut record = execute_recorded_turn(&mut self.map, point)?;
+        self.last_moves = self
+            .movement
+            .move_enemies_recorded(&mut self.map, &mut record);
+        self.turn += 1;
+        Ok(record)
+    }
+
+    /// Juega un turno por cada punto ingresado, en orden
+    /// # Arguments
+    /// * `points` - Puntos donde se detonaran las bombas
+    /// # Returns
+    /// * `Result<Vec<map::Map>, Error>` - Mapa luego de cada turno, o el error del primer turno invalido indicando su numero
+    pub fn play(&mut self, points: &[Point]) -> Result<Vec<map::Map>, Error> {
+        let mut maps = Vec::new();
+        self.play_with(points, |game, _, _| maps.push(game.map.clone()))?;
+        Ok(maps)
+    }
+
+    /// Juega un turno por cada punto ingresado, en orden, avisando a `on_turn` luego de cada turno
+    /// # Arguments
+    /// * `points` - Puntos donde se detonaran las bombas
+    /// * `on_turn` - Funcion que recibe la partida luego del turno, el punto detonado y los eventos de la explosion
+    /// # Returns
+    /// * `Result<(), Error>` - Error del primer turno invalido indicando su numero, en cuyo caso no se juegan los turnos siguientes
+    pub fn play_with<F>(&mut self, points: &[Point], mut on_turn: F) -> Result<(), Error>
+    where
+        F: FnMut(&Game, &Point, &[ExplosionEvent]),
+    {
+        for point in points {
+            let events = self.play_turn(*point).map_err(|e| Error::Turn {
+                turn: self.turn + 1,
+                cause: Box::new(e),
+            })?;
+            on_turn(self, point, &events);
+        }
+        Ok(())
+    }
 }
+
 /// Ejecuta un turno del juego detonando la bomba en el punto ingresado
 /// # Arguments
 /// * `map` - Mapa del juego
 /// * `point` - Punto donde se detonara la bomba
 /// # Returns
-/// * `Result<&map::Map, String>` - Resultado de la ejecucion del turno
-pub fn execute_turn(map: &mut map::Map, point: Point) -> Result<&map::Map, String> {
-    if !map.is_point_in_map(&point) {
-        return Err(String::from(
-            "ERROR: [Las coordenadas a detonar no se encuentran en el mapa].",
-        ));
-    }
-
-    match map.at(&point) {
-        Item::Bomb(_) | Item::PiercingBomb(_) => {}
-        _ => {
-            return Err(String::from(
-                "ERROR: [Las coordenadas ingresadas no corresponden a ninguna bomba].",
-            ));
-        }
+/// * `Result<&map::Map, Error>` - Resultado de la ejecucion del turno
+pub fn execute_turn(map: &mut map::Map, point: Point) -> Result<&map::Map, Error> {
+    execute_traced_turn(map, point)?;
+    Ok(map)
+}
+
+/// Ejecuta un turno del juego detonando la bomba en el punto ingresado y registrando los eventos de la explosion
+/// # Arguments
+/// * `map` - Mapa del juego
+/// * `point` - Punto donde se detonara la bomba
+/// # Returns
+/// * `Result<Vec<ExplosionEvent>, Error>` - Eventos de la explosion en el orden en que ocurrieron
+pub fn execute_traced_turn(map: &mut map::Map, point: Point) -> Result<Vec<ExplosionEvent>, Error> {
+    Ok(execute_recorded_turn(map, point)?.events)
+}
+
+/// Ejecuta un turno del juego: detona la bomba en el punto ingresado y luego descuenta un turno de los temporizadores,
+/// haciendo explotar las bombas con temporizador que llegan a cero.
+/// # Arguments
+/// * `map` - Mapa del juego
+/// * `point` - Punto donde se detonara la bomba
+/// # Returns
+/// * `Result<map::Detonation, Error>` - Registro del turno completo, con la detonacion elegida seguida de las de los temporizadores
+pub fn execute_recorded_turn(map: &mut map::Map, point: Point) -> Result<map::Detonation, Error> {
+    check_detonation(map, &point)?;
+    let mut record = map.detonate_bomb_recorded(&point)?;
+    let fuses = map.tick_fuses()?;
+    record.events.extend(fuses.events);
+    record.changes.extend(fuses.changes);
+    Ok(record)
+}
+
+/// Verifica que el jugador pueda detonar la bomba en el punto ingresado
+/// # Arguments
+/// * `map` - Mapa del juego
+/// * `point` - Punto donde se detonara la bomba
+/// # Returns
+/// * `Result<(), Error>` - Error si el punto esta fuera del mapa o no contiene una bomba
+pub fn check_detonation(map: &map::Map, point: &Point) -> Result<(), Error> {
+    if !map.is_point_in_map(point) {
+        return Err(Error::CoordinatesOutsideMap);
     }
 
-    map.detonate_bomb(&point)?;
-    Ok(map)
+    if map.at(point).is_bomb() {
+        Ok(())
+    } else {
+        Err(Error::NotABomb)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{direction::Direction, map_elements::Item};
     use std::fs;
 
     #[test]
@@ -119,6 +356,208 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_validate_turns_input_ignores_unpaired_argument() -> Result<(), std::io::Error> {
+        let args: Vec<String> = [
+            "",
+            "test_dir/input.txt",
+            "test_dir/output_extra",
+            "1",
+            "2",
+            "extra",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+
+        let (_, _, x, y) = validate_input(args.clone()).unwrap();
+        assert_eq!((x, y), (1, 2));
+
+        let mut args = args;
+        args[5] = String::from("3");
+        let (_, _, points) = validate_turns_input(args, None).unwrap();
+        assert_eq!(points, vec![Point::new(1, 2)]);
+        fs::remove_dir_all("test_dir/output_extra")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_take_flag() {
+        let mut args = vec![
+            String::from(""),
+            String::from("--trace"),
+            String::from("test_dir/map.txt"),
+        ];
+
+        assert!(take_flag(&mut args, "--trace"));
+        assert_eq!(
+            args,
+            vec![String::from(""), String::from("test_dir/map.txt")]
+        );
+        assert!(!take_flag(&mut args, "--trace"));
+    }
+
+    #[test]
+    fn test_parse_coordinates() {
+        let values: Vec<String> = ["1", "2", "3", "4"].iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            parse_coordinates(&values),
+            Ok(vec![Point::new(1, 2), Point::new(3, 4)])
+        );
+        assert_eq!(
+            parse_coordinates(&values[..3]),
+            Err(Error::MissingArguments)
+        );
+        assert_eq!(
+            parse_coordinates(&[String::from("a"), String::from("1")]),
+            Err(Error::InvalidCoordinates)
+        );
+    }
+
+    #[test]
+    fn test_read_moves() {
+        assert_eq!(
+            read_moves("test_dir/moves.txt"),
+            Ok(vec![Point::new(2, 4), Point::new(6, 6)])
+        );
+        assert_eq!(
+            read_moves("test_dir/map.txt"),
+            Err(Error::InvalidCoordinates)
+        );
+    }
+
+    #[test]
+    fn test_game_play() -> Result<(), Error> {
+        let mut game = Game::new(map::Map::new("test_dir/map_with_bomb_and_enemies.txt")?);
+        let maps = game.play(&[Point::new(2, 4), Point::new(6, 6)])?;
+
+        assert_eq!(game.turn(), 2);
+        assert_eq!(maps.len(), 2);
+        assert_eq!(maps[0].at(&Point::new(6, 6)), &Item::Bomb(1));
+        assert_eq!(maps[1].at(&Point::new(6, 6)), &Item::Empty);
+        assert_eq!(game.map().at(&Point::new(4, 2)), &Item::Enemy(1));
+
+        let err = game.play(&[Point::new(6, 6)]).err();
+        assert_eq!(
+            err,
+            Some(Error::Turn {
+                turn: 3,
+                cause: Box::new(Error::NotABomb)
+            })
+        );
+        assert_eq!(game.turn(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_game_play_with() -> Result<(), Error> {
+        let mut game = Game::new("B1 F1\nB1 _\n".parse()?);
+        let mut played = Vec::new();
+
+        let result = game.play_with(
+            &[Point::new(0, 0), Point::new(1, 0), Point::new(0, 1)],
+            |game, point, events| played.push((game.turn(), *point, events.len())),
+        );
+
+        assert_eq!(played, vec![(1, Point::new(0, 0), 8)]);
+        assert_eq!(
+            result,
+            Err(Error::Turn {
+                turn: 2,
+                cause: Box::new(Error::NotABomb)
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_game_timed_bombs() -> Result<(), Error> {
+        let mut game = Game::new(map::Map::new("test_dir/map_with_timed_bombs.txt")?);
+
+        game.play_turn(Point::new(0, 0))?;
+        assert_eq!(game.map().to_string(), "_ W T1:1\n_ W F1\nB1 W _\n");
+
+        let events = game.play_turn(Point::new(0, 2))?;
+        assert!(events.contains(&ExplosionEvent::FuseExpired {
+            point: Point::new(2, 0)
+        }));
+        assert_eq!(game.map().to_string(), "_ W _\n_ W _\n_ W _\n");
+        assert_eq!(
+            check_detonation(&"T1:1\n".parse::<map::Map>()?, &Point::new(0, 0)),
+            Err(Error::NotABomb)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_game_moving_enemies() -> Result<(), Error> {
+        let map: map::Map = "B1 _ _ F1\n_ _ _ _\nB1 _ _ _\n".parse()?;
+        let mut game = Game::with_movement(map, MovementRule::Patrol(Direction::Left));
+
+        game.play_turn(Point::new(0, 0))?;
+        assert_eq!(
+            game.last_moves(),
+            [EnemyMove {
+                from: Point::new(3, 0),
+                to: Point::new(2, 0)
+            }]
+        );
+        game.play_turn(Point::new(0, 2))?;
+        assert_eq!(game.map().to_string(), "_ F1 _ _\n_ _ _ _\n_ _ _ _\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_take_option() {
+        let mut args = vec![
+            String::from(""),
+            String::from("--dot"),
+            String::from("chain.dot"),
+            String::from("test_dir/map.txt"),
+        ];
+
+        assert_eq!(
+            take_option(&mut args, "--dot"),
+            Some(String::from("chain.dot"))
+        );
+        assert_eq!(
+            args,
+            vec![String::from(""), String::from("test_dir/map.txt")]
+        );
+        assert_eq!(take_option(&mut args, "--dot"), None);
+
+        let mut args = vec![String::from(""), String::from("--dot")];
+        assert_eq!(take_option(&mut args, "--dot"), None);
+        assert_eq!(args, vec![String::from("")]);
+    }
+
+    #[test]
+    fn test_execute_traced_turn() {
+        let mut map = map::Map::new("test_dir/map.txt").unwrap();
+        let events = execute_traced_turn(&mut map, Point::new(4, 2)).unwrap();
+
+        assert_eq!(
+            events.first(),
+            Some(&ExplosionEvent::BombDetonated {
+                point: Point::new(4, 2),
+                reach: 2,
+                piercing: false
+            })
+        );
+        assert!(events.contains(&ExplosionEvent::EnemyKilled {
+            point: Point::new(4, 0)
+        }));
+        assert_eq!(
+            execute_traced_turn(&mut map, Point::new(4, 2)),
+            Err(Error::NotABomb)
+        );
+    }
+
     #[test]
     fn test_execute_turn() {
         let mut map = map::Map::new("test_dir/map.txt").unwrap();
@@ -126,7 +565,13 @@ mod tests {
         let new_map = execute_turn(&mut map, point).unwrap();
         assert_eq!(new_map.at(&point), &Item::Empty);
 
-        assert!(execute_turn(&mut map, Point::new(0, 0)).is_err());
-        assert!(execute_turn(&mut map, Point::new(10000, 0)).is_err());
+        assert_eq!(
+            execute_turn(&mut map, Point::new(0, 0)).err(),
+            Some(Error::NotABomb)
+        );
+        assert_eq!(
+            execute_turn(&mut map, Point::new(10000, 0)).err(),
+            Some(Error::CoordinatesOutsideMap)
+        );
     }
 }
@@ -0,0 +1,311 @@
+use crate::{
+    direction::Direction,
+    error::Error,
+    map::Map,
+    map_elements::Item,
+    rng::Rng,
+    rules::Rules,
+    solve::{self, SearchBudget, SolveOutcome},
+};
+
+/// Configuracion del generador de mapas. Las densidades indican la probabilidad de que cada celda contenga ese item,
+/// por lo que su suma no puede superar 1. Las celdas restantes quedan vacias.
+/// # Attributes
+/// * `width` - Cantidad de columnas del mapa.
+/// * `height` - Cantidad de filas del mapa.
+/// * `wall_density` - Probabilidad de que una celda sea una pared.
+/// * `rock_density` - Probabilidad de que una celda sea una roca.
+/// * `enemy_density` - Probabilidad de que una celda sea un enemigo, con vida entre 1 y la vida maxima de las reglas.
+/// * `bomb_density` - Probabilidad de que una celda sea una bomba normal.
+/// * `piercing_bomb_density` - Probabilidad de que una celda sea una bomba de traspaso.
+/// * `detour_density` - Probabilidad de que una celda sea un desvio.
+/// * `max_reach` - Alcance maximo de las bombas generadas.
+/// * `solvable_in` - Si es Some(n), solo se aceptan mapas que puedan resolverse en a lo sumo n turnos.
+/// * `max_attempts` - Cantidad maxima de mapas a generar buscando uno que cumpla `solvable_in`.
+/// * `budget` - Limites de la busqueda usada para verificar `solvable_in`.
+/// * `rules` - Reglas del mapa generado, con las que tambien se verifica `solvable_in`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeneratorConfig {
+    pub width: usize,
+    pub height: usize,
+    pub wall_density: f64,
+    pub rock_density: f64,
+    pub enemy_density: f64,
+    pub bomb_density: f64,
+    pub piercing_bomb_density: f64,
+    pub detour_density: f64,
+    pub max_reach: u32,
+    pub solvable_in: Option<usize>,
+    pub max_attempts: usize,
+    pub budget: SearchBudget,
+    pub rules: Rules,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        GeneratorConfig {
+            width: 7,
+            height: 7,
+            wall_density: 0.1,
+            rock_density: 0.05,
+            enemy_density: 0.1,
+            bomb_density: 0.1,
+            piercing_bomb_density: 0.03,
+            detour_density: 0.03,
+            max_reach: 3,
+            solvable_in: None,
+            max_attempts: 100,
+            budget: SearchBudget::default(),
+            rules: Rules::default(),
+        }
+    }
+}
+
+impl GeneratorConfig {
+    fn densities(&self) -> [f64; 6] {
+        [
+            self.wall_density,
+            self.rock_density,
+            self.enemy_density,
+            self.bomb_density,
+            self.piercing_bomb_density,
+            self.detour_density,
+        ]
+    }
+
+    /// Verifica que la configuracion pueda producir un mapa valido.
+    fn check(&self) -> Result<(), Error> {
+        if self.width == 0 || self.height == 0 {
+            return Err(Error::EmptyMap);
+        }
+        let densities = self.densities();
+        if densities
+            .iter()
+            .any(|density| !(0.0..=1.0).contains(density))
+            || densities.iter().sum::<f64>() > 1.0
+            || self.max_reach == 0
+            || self.rules.max_enemy_health == 0
+        {
+            return Err(Error::InvalidGeneratorConfig);
+        }
+        Ok(())
+    }
+}
+
+/// Genera un mapa aleatorio. La misma configuracion y la misma semilla producen siempre el mismo mapa.
+/// # Arguments
+/// * `config` - Configuracion del generador
+/// * `seed` - Semilla del generador de numeros aleatorios
+/// # Returns
+/// * `Result<Map, Error>` - Mapa generado, o error si la configuracion es invalida o no se encontro un mapa que pueda resolverse en los turnos pedidos
+pub fn generate(config: &GeneratorConfig, seed: u64) -> Result<Map, Error> {
+    config.check()?;
+    let mut rng = Rng::new(seed);
+
+    let max_turns = match config.solvable_in {
+        Some(max_turns) => max_turns,
+        None => return Ok(generate_map(config, &mut rng)),
+    };
+
+    for _ in 0..config.max_attempts {
+        let map = generate_map(config, &mut rng);
+        // Un mapa sin enemigos ya esta resuelto, por lo que no sirve como desafio.
+        if !solve::has_enemies(&map) {
+            continue;
+        }
+        if let SolveOutcome::Solved(sequence) = solve::solve_sequence(&map, &config.budget) {
+            if sequence.len() <= max_turns {
+                return Ok(map);
+            }
+        }
+    }
+    Err(Error::GenerationFailed {
+        attempts: config.max_attempts,
+    })
+}
+
+fn generate_map(config: &GeneratorConfig, rng: &mut Rng) -> Map {
+    let rows = (0..config.height)
+        .map(|_| {
+            (0..config.width)
+                .map(|_| generate_item(config, rng))
+                .collect()
+        })
+        .collect();
+    // Todas las filas tienen el mismo ancho, por lo que no puede fallar.
+    let mut map = Map::from_rows(rows).unwrap_or_default();
+    map.set_rules(config.rules);
+    map
+}
+
+fn generate_item(config: &GeneratorConfig, rng: &mut Rng) -> Item {
+    let roll = rng.next_f64();
+    let mut threshold = 0.0;
+    let mut kind = None;
+    for (index, density) in config.densities().iter().enumerate() {
+        threshold += density;
+        if roll < threshold {
+            kind = Some(index);
+            break;
+        }
+    }
+
+    match kind {
+        Some(0) => Item::Wall,
+        Some(1) => Item::Rock,
+        Some(2) => Item::Enemy(rng.between(1, config.rules.max_enemy_health)),
+        Some(3) => Item::Bomb(rng.between(1, config.max_reach)),
+        Some(4) => Item::PiercingBomb(rng.between(1, config.max_reach)),
+        Some(5) => Item::Detour(Direction::ORTHOGONAL[rng.below(Direction::ORTHOGONAL.len())]),
+        _ => Item::Empty,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_is_reproducible() -> Result<(), Error> {
+        let config = GeneratorConfig::default();
+        let map = generate(&config, 1234)?;
+
+        assert_eq!(map.width(), 7);
+        assert_eq!(map.height(), 7);
+        assert_eq!(map, generate(&config, 1234)?);
+        assert_ne!(map, generate(&config, 4321)?);
+
+        let reparsed: Map = map.to_string().parse()?;
+        assert_eq!(reparsed, map);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_densities() -> Result<(), Error> {
+        let config = GeneratorConfig {
+            width: 4,
+            height: 3,
+            wall_density: 1.0,
+            rock_density: 0.0,
+            enemy_density: 0.0,
+            bomb_density: 0.0,
+            piercing_bomb_density: 0.0,
+            detour_density: 0.0,
+            ..GeneratorConfig::default()
+        };
+        let map = generate(&config, 0)?;
+
+        assert_eq!(map.count_items(|item| item == &Item::Wall), 12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_with_rules() -> Result<(), Error> {
+        let rules = Rules {
+            max_enemy_health: 9,
+            ..Rules::default()
+        };
+        let config = GeneratorConfig {
+            enemy_density: 1.0,
+            wall_density: 0.0,
+            rock_density: 0.0,
+            bomb_density: 0.0,
+            piercing_bomb_density: 0.0,
+            detour_density: 0.0,
+            rules,
+            ..GeneratorConfig::default()
+        };
+        let map = generate(&config, 5)?;
+
+        assert_eq!(map.rules(), &rules);
+        assert_eq!(
+            map.count_items(|item| matches!(item, Item::Enemy(1..=9))),
+            49
+        );
+        assert!(map.count_items(|item| matches!(item, Item::Enemy(4..))) > 0);
+
+        let config = GeneratorConfig {
+            rules: Rules {
+                max_enemy_health: 0,
+                ..Rules::default()
+            },
+            ..GeneratorConfig::default()
+        };
+        assert_eq!(generate(&config, 0), Err(Error::InvalidGeneratorConfig));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_solvable() -> Result<(), Error> {
+        let config = GeneratorConfig {
+            width: 5,
+            height: 5,
+            solvable_in: Some(2),
+            ..GeneratorConfig::default()
+        };
+        let map = generate(&config, 99)?;
+
+        match solve::solve_sequence(&map, &SearchBudget::default()) {
+            SolveOutcome::Solved(sequence) => assert!(sequence.len() <= 2),
+            outcome => panic!("el mapa generado no tiene solucion: {:?}", outcome),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_invalid_config() {
+        let config = GeneratorConfig {
+            wall_density: 0.8,
+            rock_density: 0.8,
+            ..GeneratorConfig::default()
+        };
+        assert_eq!(generate(&config, 0), Err(Error::InvalidGeneratorConfig));
+
+        let config = GeneratorConfig {
+            width: 0,
+            ..GeneratorConfig::default()
+        };
+        assert_eq!(generate(&config, 0), Err(Error::EmptyMap));
+    }
+
+    #[test]
+    fn test_generate_failed() {
+        let config = GeneratorConfig {
+            width: 2,
+            height: 1,
+            wall_density: 0.0,
+            rock_density: 0.0,
+            enemy_density: 1.0,
+            bomb_density: 0.0,
+            piercing_bomb_density: 0.0,
+            detour_density: 0.0,
+            solvable_in: Some(1),
+            max_attempts: 3,
+            ..GeneratorConfig::default()
+        };
+
+        assert_eq!(
+            generate(&config, 0),
+            Err(Error::GenerationFailed { attempts: 3 })
+        );
+    }
+
+    #[test]
+    fn test_generate_rejects_maps_without_enemies() {
+        let config = GeneratorConfig {
+            enemy_density: 0.0,
+            solvable_in: Some(1),
+            max_attempts: 3,
+            ..GeneratorConfig::default()
+        };
+
+        assert_eq!(
+            generate(&config, 0),
+            Err(Error::GenerationFailed { attempts: 3 })
+        );
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{
+    error::Error,
+    explosion::ExplosionEvent,
+    game,
+    map::{CellChange, Map},
+    movement::{EnemyMovement, MovementRule},
+    point::Point,
+};
+
+/// Historial de detonaciones sobre un mapa que permite deshacer, rehacer y saltar a cualquier turno.
+/// Por cada turno se guardan unicamente las celdas que modificaron la explosion y el movimiento de los enemigos,
+/// en lugar de una copia del mapa, junto con el estado del movimiento luego del turno.
+#[derive(Debug, Clone)]
+pub struct History {
+    map: Map,
+    turns: Vec<Vec<CellChange>>,
+    movements: Vec<EnemyMovement>,
+    current: usize,
+}
+
+impl History {
+    /// Crea un historial vacio a partir del mapa inicial, con enemigos que no se mueven
+    /// # Arguments
+    /// * `map` - Mapa inicial
+    pub fn new(map: Map) -> Self {
+        History::with_movement(map, MovementRule::Static)
+    }
+
+    /// Crea un historial vacio a partir del mapa inicial, moviendo a los enemigos luego de cada turno
+    /// # Arguments
+    /// * `map` - Mapa inicial
+    /// * `rule` - Regla de movimiento de los enemigos
+    pub fn with_movement(map: Map, rule: MovementRule) -> Self {
+        History {
+            map,
+            turns: Vec::new(),
+            movements: vec![EnemyMovement::new(rule)],
+            current: 0,
+        }
+    }
+
+    /// Devuelve el mapa en el turno actual
+    pub fn map(&self) -> &Map {
+        &self.map
+    }
+
+    /// Devuelve el turno actual, donde 0 es el mapa inicial
+    pub fn current_turn(&self) -> usize {
+        self.current
+    }
+
+    /// Devuelve la cantidad de turnos registrados, incluyendo los que pueden rehacerse
+    pub fn len(&self) -> usize {
+        self.turns.len()
+    }
+
+    /// Devuelve true si no se registro ninguna detonacion
+    pub fn is_empty(&self) -> bool {
+        self.turns.is_empty()
+    }
+
+    /// Detona la bomba en el punto ingresado, mueve a los enemigos y registra el turno. Los turnos que podian rehacerse se descartan.
+    /// # Arguments
+    /// * `point` - Punto donde se detonara la bomba
+    /// # Returns
+    /// * `Result<Vec<ExplosionEvent>, Error>` - Eventos de la explosion, o el error en caso de que el punto no sea valido
+    pub fn detonate(&mut self, point: &Point) -> Result<Vec<ExplosionEvent>, Error> {
+        let mut record = game::execute_recorded_turn(&mut self.map, *point)?;
+        let mut movement = self.movements[self.current].clone();
+        movement.move_enemies_recorded(&mut self.map, &mut record);
+
+        self.turns.truncate(self.current);
+        self.turns.push(record.changes);
+        self.movements.truncate(self.current + 1);
+        self.movements.push(movement);
+        self.current += 1;
+        Ok(record.events)
+    }
+
+    /// Deshace el ultimo turno
+    /// # Returns
+    /// * `bool` - True si habia un turno para deshacer
+    pub fn undo(&mut self) -> bool {
+        if self.current == 0 {
+            return false;
+        }
+        self.current -= 1;
+        for change in self.turns[self.current].iter().rev() {
+            self.map.set_at(&change.point, change.before.clone());
+        }
+        true
+    }
+
+    /// Rehace el ultimo turno deshecho
+    /// # Returns
+    /// * `bool` - True si habia un turno para rehacer
+    pub fn redo(&mut self) -> bool {
+        if self.current == self.turns.len() {
+            return false;
+        }
+        for change in &self.turns[self.current] {
+            self.map.set_at(&change.point, change.after.clone());
+        }
+        self.current += 1;
+        true
+    }
+
+    /// Lleva el mapa al estado luego del turno indicado, deshaciendo o rehaciendo los turnos necesarios
+    /// # Arguments
+    /// * `turn` - Turno al que se quiere ir, donde 0 es el mapa inicial
+    /// # Returns
+    /// * `bool` - True si el turno existe en el historial
+    pub fn jump_to(&mut self, turn: usize) -> bool {
+        if turn > self.turns.len() {
+            return false;
+        }
+        while self.current > turn {
+            self.undo();
+        }
+        while self.current < turn {
+            self.redo();
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{direction::Direction, map_elements::Item};
+
+    #[test]
+    fn test_history_undo_redo() -> Result<(), Error> {
+        let map = Map::new("test_dir/map_with_bomb_and_enemies.txt")?;
+        let initial = map.to_string();
+        let mut history = History::new(map);
+
+        history.detonate(&Point::new(2, 4))?;
+        let after_first = history.map().to_string();
+        history.detonate(&Point::new(6, 6))?;
+        let after_second = history.map().to_string();
+
+        assert_eq!(history.len(), 2);
+        assert!(history.undo());
+        assert_eq!(history.map().to_string(), after_first);
+        assert!(history.undo());
+        assert_eq!(history.map().to_string(), initial);
+        assert!(!history.undo());
+
+        assert!(history.redo());
+        assert!(history.redo());
+        assert_eq!(history.map().to_string(), after_second);
+        assert!(!history.redo());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_history_jump_to() -> Result<(), Error> {
+        let map = Map::new("test_dir/map_with_bomb_and_enemies.txt")?;
+        let mut history = History::new(map);
+
+        history.detonate(&Point::new(2, 4))?;
+        history.detonate(&Point::new(6, 6))?;
+
+        assert!(history.jump_to(0));
+        assert_eq!(history.map().at(&Point::new(2, 4)), &Item::Bomb(4));
+        assert!(history.jump_to(2));
+        assert_eq!(history.current_turn(), 2);
+        assert_eq!(history.map().at(&Point::new(6, 6)), &Item::Empty);
+        assert!(!history.jump_to(3));
+
+        history.jump_to(1);
+        assert_eq!(history.detonate(&Point::new(2, 4)), Err(Error::NotABomb));
+        history.detonate(&Point::new(6, 6))?;
+        assert_eq!(history.len(), 2);
+        assert!(!history.redo());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_history_with_moving_enemies() -> Result<(), Error> {
+        let map: Map = "B1 _ _ _\nF2 _ _ _\n".parse()?;
+        let initial = map.to_string();
+        let mut history = History::with_movement(map, MovementRule::Patrol(Direction::Right));
+
+        history.detonate(&Point::new(0, 0))?;
+        let after_first = history.map().to_string();
+        assert_eq!(after_first, "_ _ _ _\n_ F1 _ _\n");
+
+        assert!(history.undo());
+        assert_eq!(history.map().to_string(), initial);
+        assert!(history.redo());
+        assert_eq!(history.map().to_string(), after_first);
+
+        history.undo();
+        history.detonate(&Point::new(0, 0))?;
+        assert_eq!(history.map().to_string(), after_first);
+
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use std::fs;
 
-use crate::map::Map;
+use crate::{error::Error, map::Map};
 
 /// Crea un directorio en caso de que no exista.
 /// * `output_dir` - Nombre del directorio a crear.
@@ -19,16 +19,16 @@ pub fn create_directory(output_dir: &str) {
     };
 }
 
-/// Escribe un mensaje de error en un archivo siguiendo el formato de la catedra.
+/// Escribe un texto en un archivo, creando el directorio que lo contiene en caso de que no exista.
 /// * `output_file` - Nombre del archivo de salida.
-/// * `mensaje` - Mensaje de error a escribir en el archivo
-pub fn write_error(output_file: &str, mensaje: &str) {
+/// * `contents` - Texto a escribir en el archivo.
+pub fn write_file(output_file: &str, contents: &str) {
     let mut dir = output_file.split('/').collect::<Vec<&str>>();
     dir.pop();
     if !dir.is_empty() {
         create_directory(&dir.join("/"));
     }
-    match fs::write(output_file, mensaje) {
+    match fs::write(output_file, contents) {
         Ok(_) => {}
         Err(e) => {
             println!("ERROR: [{e}].");
@@ -36,34 +36,25 @@ pub fn write_error(output_file: &str, mensaje: &str) {
     };
 }
 
+/// Escribe un mensaje de error en un archivo siguiendo el formato de la catedra.
+/// * `output_file` - Nombre del archivo de salida.
+/// * `mensaje` - Mensaje de error a escribir en el archivo
+pub fn write_error(output_file: &str, mensaje: &str) {
+    write_file(output_file, mensaje);
+}
+
 /// Lee un archivo y devuelve su contenido.
 /// * `input_file` - Nombre del archivo a leer.
-/// * `Result<String, String>` - Contenido del archivo o mensaje de error.
-pub fn read_file(input_file: &str) -> Result<String, String> {
-    match fs::read_to_string(input_file) {
-        Ok(s) => Ok(s),
-        Err(_) => Err(String::from(
-            "ERROR: [No se pudo leer el archivo de entrada].",
-        )),
-    }
+/// * `Result<String, Error>` - Contenido del archivo o error.
+pub fn read_file(input_file: &str) -> Result<String, Error> {
+    Ok(fs::read_to_string(input_file)?)
 }
 
 /// Escribe el mapa en un archivo siguiendo el formato de la catedra.
 /// * `output_file` - Nombre del archivo de salida.
 /// * `map` - Mapa a escribir en el archivo.
-/// * `Result<(), String>` - Mensaje de error en caso de que no se pueda escribir el archivo.
 pub fn write_output(output_file: &str, map: &Map) {
-    let mut dir = output_file.split('/').collect::<Vec<&str>>();
-    dir.pop();
-    if !dir.is_empty() {
-        create_directory(&dir.join("/"));
-    }
-    match fs::write(output_file, map.to_string()) {
-        Ok(_) => {}
-        Err(e) => {
-            println!("ERROR: [{e}].");
-        }
-    };
+    write_file(output_file, &map.to_string());
 }
 
 #[cfg(test)]
@@ -71,7 +62,7 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_read_file() -> Result<(), String> {
+    fn test_read_file() -> Result<(), Error> {
         let file = read_file("test_dir/empty.txt")?;
         assert_eq!(file, "");
 
@@ -81,11 +72,16 @@ mod tests {
         let file = read_file("test_dir/multiple_lines.txt")?;
         assert_eq!(file, "1 2 3 4 5\n6 7 8 9 10\n11 12 13 14 15");
 
+        assert_eq!(
+            read_file("test_dir/does_not_exist.txt"),
+            Err(Error::Io(std::io::ErrorKind::NotFound))
+        );
+
         Ok(())
     }
 
     #[test]
-    fn test_write_output() -> Result<(), String> {
+    fn test_write_output() -> Result<(), Error> {
         let map = Map::new("test_dir/map.txt")?;
         write_output("test_dir/output.txt", &map);
 
@@ -93,10 +89,8 @@ mod tests {
         let output = read_file("test_dir/output.txt")?;
         assert_eq!(input, output);
 
-        match fs::remove_file("test_dir/output.txt") {
-            Ok(_) => Ok(()),
-            Err(e) => Err(e.to_string()),
-        }
+        fs::remove_file("test_dir/output.txt")?;
+        Ok(())
     }
 
     #[test]
@@ -110,7 +104,7 @@ mod tests {
     }
 
     #[test]
-    fn test_write_error() -> Result<(), String> {
+    fn test_write_error() -> Result<(), Error> {
         write_error("test_dir/error.txt", "ERROR: [Error de prueba].");
         let file = read_file("test_dir/error.txt")?;
         assert_eq!(file, "ERROR: [Error de prueba].");
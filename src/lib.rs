@@ -1,8 +1,20 @@
+pub mod chain;
+pub mod coverage;
 pub mod detour;
+pub mod difficulty;
 pub mod direction;
+pub mod error;
+pub mod explosion;
 pub mod game;
+pub mod generator;
+pub mod history;
 pub mod io;
 pub mod map;
 pub mod map_elements;
+pub mod mirror;
+pub mod movement;
 pub mod point;
+pub mod rng;
+pub mod rules;
+pub mod solve;
 pub mod utils;
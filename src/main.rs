@@ -1,39 +1,336 @@
 use std::env;
 
-mod detour;
-mod direction;
-mod game;
-mod io;
-mod map;
-mod map_elements;
-mod point;
-mod utils;
-
-use map::Map;
-use point::Point;
+use tp_1::{
+    chain::ChainNode,
+    coverage::Heatmap,
+    difficulty::Difficulty,
+    error::Error,
+    game::{self, Game},
+    generator::{self, GeneratorConfig},
+    io,
+    map::{Map, ParseOptions},
+    movement::MovementRule,
+    rules::Rules,
+    solve::{self, SearchBudget, SolveOutcome},
+};
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let trace = game::take_flag(&mut args, "--trace");
+    let dot_file = game::take_option(&mut args, "--dot");
+    let moves_file = game::take_option(&mut args, "--moves");
+    let all_turns = game::take_flag(&mut args, "--all-turns");
+    let solvable_in = game::take_option(&mut args, "--solvable-in");
+    let enemies = game::take_option(&mut args, "--enemies");
+    let rules_file = game::take_option(&mut args, "--rules");
 
-    let (input_file, output_file, x, y) = match game::validate_input(args) {
-        Some((input_file, output_file, x, y)) => (input_file, output_file, x, y),
-        None => return,
+    if let Some(mode) = selected_mode(&args, moves_file.as_deref()) {
+        // Estos modos no tienen archivo de salida para los errores, por lo que los imprimen.
+        let rules = match load_rules(rules_file.as_deref()) {
+            Ok(rules) => rules,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        };
+        let options = ParseOptions {
+            rules,
+            ..ParseOptions::default()
+        };
+        match mode {
+            "validate" => run_validate(&args, &rules),
+            "generate" => run_generate(&args, solvable_in.as_deref(), rules),
+            "difficulty" => run_difficulty(&args, &options),
+            "heatmap" => run_heatmap(&args, &options),
+            _ => run_solve(&args, &options),
+        }
+        return;
+    }
+
+    let (input_file, output_file, points) =
+        match game::validate_turns_input(args, moves_file.as_deref()) {
+            Some((input_file, output_file, points)) => (input_file, output_file, points),
+            None => return,
+        };
+
+    let movement = match enemies.as_deref().map(MovementRule::parse).transpose() {
+        Ok(movement) => movement.unwrap_or_default(),
+        Err(e) => {
+            io::write_error(&output_file, &e.to_string());
+            return;
+        }
+    };
+
+    let options = match load_rules(rules_file.as_deref()) {
+        Ok(rules) => ParseOptions {
+            rules,
+            ..ParseOptions::default()
+        },
+        Err(e) => {
+            io::write_error(&output_file, &e.to_string());
+            return;
+        }
+    };
+
+    let mut game = match Map::new_with(&input_file, &options) {
+        Ok(map) => Game::with_movement(map, movement),
+        Err(e) => {
+            io::write_error(&output_file, &e.to_string());
+            return;
+        }
+    };
+
+    let mut dot = String::new();
+    let mut turn_maps = Vec::new();
+    // El mapa luego de cada turno es el mapa previo al siguiente, del que se leen las bombas del arbol de reacciones.
+    let mut previous_map = dot_file.as_ref().map(|_| game.map().clone());
+    let played = game.play_with(&points, |game, point, events| {
+        if let Some(previous_map) = &mut previous_map {
+            dot.push_str(&ChainNode::from_events(previous_map, point, events).to_dot());
+            *previous_map = game.map().clone();
+        }
+        if trace {
+            for event in events {
+                println!("{}", event);
+            }
+            for enemy_move in game.last_moves() {
+                println!("{}", enemy_move);
+            }
+        }
+        if all_turns {
+            turn_maps.push(game.map().to_string());
+        }
+    });
+    if let Err(e) = played {
+        io::write_error(&output_file, &e.to_string());
+        return;
+    }
+
+    if let Some(dot_file) = &dot_file {
+        io::write_file(dot_file, &dot);
+    }
+    if all_turns {
+        io::write_file(&output_file, &turn_maps.join("\n"));
+    } else {
+        io::write_output(&output_file, game.map());
+    }
+}
+
+/// Devuelve el modo indicado en los argumentos, o None si se debe jugar una partida. Un archivo de mapa puede llamarse
+/// igual que un modo: `validate`, `difficulty`, `heatmap` y `solve` solo se toman como modo cuando reciben un unico
+/// argumento, que no alcanza para jugar. `generate` es un nombre reservado, porque sus argumentos tambien forman una
+/// partida valida, por lo que un mapa con ese nombre debe indicarse con su ruta, por ejemplo `./generate`.
+/// * `args` - Argumentos del programa, sin las opciones.
+/// * `moves_file` - Archivo de jugadas ingresado con `--moves`, si se indico.
+fn selected_mode<'a>(args: &'a [String], moves_file: Option<&str>) -> Option<&'a str> {
+    match args.get(1)?.as_str() {
+        "generate" => Some("generate"),
+        mode @ ("validate" | "difficulty" | "heatmap" | "solve")
+            if args.len() == 3 && moves_file.is_none() =>
+        {
+            Some(mode)
+        }
+        _ => None,
+    }
+}
+
+/// Lee las reglas del archivo indicado, o devuelve las reglas por defecto si no se indico ninguno.
+/// * `rules_file` - Archivo de reglas ingresado con `--rules`, si se indico.
+fn load_rules(rules_file: Option<&str>) -> Result<Rules, Error> {
+    Ok(rules_file
+        .map(Rules::from_file)
+        .transpose()?
+        .unwrap_or_default())
+}
+
+/// Valida el mapa ingresado e imprime un reporte con todos los errores encontrados.
+/// * `args` - Argumentos del programa, de la forma `validate <archivo de entrada>`.
+/// * `rules` - Reglas con las que se validan los items del mapa.
+fn run_validate(args: &[String], rules: &Rules) {
+    let input_file = match args.get(2) {
+        Some(input_file) => input_file,
+        None => {
+            println!("{}", Error::MissingArguments);
+            return;
+        }
+    };
+
+    let map_raw = match io::read_file(input_file) {
+        Ok(map_raw) => map_raw,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
     };
 
-    let point_to_detonate = Point::new(x, y);
+    let errors = Map::validate_with(&map_raw, rules);
+    if errors.is_empty() {
+        println!("El mapa {} es valido.", input_file);
+        return;
+    }
 
-    let mut map = match Map::new(&input_file) {
+    for error in &errors {
+        println!("{}\n", error.render_snippet(&map_raw));
+    }
+    println!("Se encontraron {} errores en {}.", errors.len(), input_file);
+}
+
+/// Genera un mapa aleatorio y lo escribe en el archivo de salida.
+/// * `args` - Argumentos del programa, de la forma `generate <ancho> <alto> <semilla> <archivo de salida>`.
+/// * `solvable_in` - Cantidad maxima de turnos en la que debe poder resolverse el mapa, si se indico.
+/// * `rules` - Reglas del mapa generado, que limitan la vida de los enemigos y se usan para verificar `solvable_in`.
+fn run_generate(args: &[String], solvable_in: Option<&str>, rules: Rules) {
+    let (width, height, seed, output_file) =
+        match (args.get(2), args.get(3), args.get(4), args.get(5)) {
+            (Some(width), Some(height), Some(seed), Some(output_file)) => {
+                (width, height, seed, output_file)
+            }
+            _ => {
+                println!("{}", Error::MissingArguments);
+                return;
+            }
+        };
+
+    let config = match (
+        width.parse(),
+        height.parse(),
+        solvable_in.map(str::parse).transpose(),
+    ) {
+        (Ok(width), Ok(height), Ok(solvable_in)) => GeneratorConfig {
+            width,
+            height,
+            solvable_in,
+            rules,
+            ..GeneratorConfig::default()
+        },
+        _ => {
+            println!("{}", Error::InvalidGeneratorConfig);
+            return;
+        }
+    };
+    let seed = match seed.parse() {
+        Ok(seed) => seed,
+        Err(_) => {
+            println!("{}", Error::InvalidGeneratorConfig);
+            return;
+        }
+    };
+
+    match generator::generate(&config, seed) {
+        Ok(map) => io::write_output(output_file, &map),
+        Err(e) => println!("{}", e),
+    }
+}
+
+/// Calcula la dificultad del mapa ingresado e imprime el puntaje junto con las metricas que lo componen.
+/// * `args` - Argumentos del programa, de la forma `difficulty <archivo de entrada>`.
+/// * `options` - Opciones con las que se parsea el mapa, incluyendo sus reglas.
+fn run_difficulty(args: &[String], options: &ParseOptions) {
+    let input_file = match args.get(2) {
+        Some(input_file) => input_file,
+        None => {
+            println!("{}", Error::MissingArguments);
+            return;
+        }
+    };
+
+    match Map::new_with(input_file, options) {
+        Ok(map) => print!("{}", Difficulty::rate(&map, &SearchBudget::default())),
+        Err(e) => println!("{}", e),
+    }
+}
+
+/// Imprime el mapa de calor de las bombas del mapa ingresado y los enemigos que ninguna bomba alcanza.
+/// * `args` - Argumentos del programa, de la forma `heatmap <archivo de entrada>`.
+/// * `options` - Opciones con las que se parsea el mapa, incluyendo sus reglas.
+fn run_heatmap(args: &[String], options: &ParseOptions) {
+    let input_file = match args.get(2) {
+        Some(input_file) => input_file,
+        None => {
+            println!("{}", Error::MissingArguments);
+            return;
+        }
+    };
+
+    let map = match Map::new_with(input_file, options) {
         Ok(map) => map,
         Err(e) => {
-            io::write_error(&output_file, &e);
+            println!("{}", e);
+            return;
+        }
+    };
+
+    let heatmap = Heatmap::from_map(&map);
+    print!("{}", heatmap);
+    for enemy in heatmap.unreachable_enemies(&map) {
+        println!(
+            "El enemigo {} ({}, {}) no puede ser alcanzado por ninguna bomba.",
+            map.at(&enemy),
+            enemy.x,
+            enemy.y
+        );
+    }
+}
+
+/// Simula cada bomba del mapa ingresado e imprime el ranking de mejor a peor.
+/// * `args` - Argumentos del programa, de la forma `solve <archivo de entrada>`.
+/// * `options` - Opciones con las que se parsea el mapa, incluyendo sus reglas.
+fn run_solve(args: &[String], options: &ParseOptions) {
+    let input_file = match args.get(2) {
+        Some(input_file) => input_file,
+        None => {
+            println!("{}", Error::MissingArguments);
             return;
         }
     };
 
-    match game::execute_turn(&mut map, point_to_detonate) {
-        Ok(new_map) => io::write_output(&output_file, new_map),
+    let map = match Map::new_with(input_file, options) {
+        Ok(map) => map,
         Err(e) => {
-            io::write_error(&output_file, &e);
+            println!("{}", e);
+            return;
+        }
+    };
+
+    let ranking = solve::rank_bombs(&map);
+    if ranking.is_empty() {
+        println!("El mapa {} no contiene bombas.", input_file);
+        return;
+    }
+
+    for (position, score) in ranking.iter().enumerate() {
+        println!(
+            "{}. {} ({}, {}): enemigos eliminados={} daño={} rocas restantes={} celdas alcanzadas={}",
+            position + 1,
+            map.at(&score.point),
+            score.point.x,
+            score.point.y,
+            score.enemies_killed,
+            score.damage_dealt,
+            score.rocks_left,
+            score.cells_burned
+        );
+    }
+
+    match solve::solve_sequence(&map, &SearchBudget::default()) {
+        SolveOutcome::Solved(sequence) => {
+            let moves: Vec<String> = sequence
+                .iter()
+                .map(|point| format!("({}, {})", point.x, point.y))
+                .collect();
+            println!(
+                "Secuencia minima ({} turnos): {}",
+                sequence.len(),
+                moves.join(" -> ")
+            );
         }
+        SolveOutcome::Unsolvable { explored } => println!(
+            "El mapa no tiene solucion, se recorrieron {} estados.",
+            explored
+        ),
+        SolveOutcome::BudgetExceeded { explored } => println!(
+            "No se encontro una solucion luego de recorrer {} estados.",
+            explored
+        ),
     }
 }
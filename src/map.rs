@@ -1,13 +1,100 @@
-use std::{collections::HashMap, fmt};
-
-use crate::{direction::Direction, io, map_elements::Item, point::Point};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    io::Read,
+    str::FromStr,
+};
+
+use crate::{
+    direction::Direction,
+    error::{Error, Position},
+    explosion::ExplosionEvent,
+    io,
+    map_elements::Item,
+    point::Point,
+    rules::{DamagePolicy, Rules},
+};
 
 /// El mapa es representado como un vector de vectores de items, donde cada posicion contiene el item que se encuentra en esa posicion.
-pub struct Map(Vec<Vec<Item>>);
+/// Todas las filas tienen la misma cantidad de items, por lo que el ancho y el alto se guardan explicitamente.
+/// Tambien guarda las reglas con las que se juega, que se aplican al detonar bombas, y la pareja de cada
+/// teletransportador, que se calcula al crear el mapa.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Map {
+    rows: Vec<Vec<Item>>,
+    width: usize,
+    height: usize,
+    rules: Rules,
+    teleporters: Vec<(Point, Point)>,
+}
+
+/// Cambio de una celda del mapa producido por una explosion.
+/// # Attributes
+/// * `point` - Celda modificada.
+/// * `before` - Item que habia en la celda antes del cambio.
+/// * `after` - Item que quedo en la celda luego del cambio.
+#[derive(Debug, PartialEq, Clone)]
+pub struct CellChange {
+    pub point: Point,
+    pub before: Item,
+    pub after: Item,
+}
+
+/// Registro de una detonacion, incluyendo las bombas encadenadas.
+/// # Attributes
+/// * `events` - Eventos de la explosion en el orden en que ocurrieron.
+/// * `changes` - Celdas modificadas en el orden en que cambiaron. Una celda puede aparecer mas de una vez.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Detonation {
+    pub events: Vec<ExplosionEvent>,
+    pub changes: Vec<CellChange>,
+}
+
+/// Resumen de una detonacion simulada.
+/// # Attributes
+/// * `enemies_killed` - Enemigos eliminados, en el orden en que murieron.
+/// * `enemies_damaged` - Enemigos que recibieron daño y sobrevivieron, junto con la vida que les quedo.
+/// * `bombs_chained` - Bombas detonadas por la reaccion en cadena, sin incluir la inicial.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct DetonationSummary {
+    pub enemies_killed: Vec<Point>,
+    pub enemies_damaged: Vec<(Point, u32)>,
+    pub bombs_chained: Vec<Point>,
+}
+
+impl DetonationSummary {
+    /// Resume los eventos de una detonacion
+    /// # Arguments
+    /// * `events` - Eventos de la detonacion
+    pub fn from_events(events: &[ExplosionEvent]) -> Self {
+        let mut summary = DetonationSummary::default();
+        for event in events {
+            match event {
+                ExplosionEvent::EnemyKilled { point } => summary.enemies_killed.push(*point),
+                ExplosionEvent::EnemyDamaged { point, health } => {
+                    summary.enemies_damaged.push((*point, *health))
+                }
+                ExplosionEvent::ChainTriggered { to, .. } => summary.bombs_chained.push(*to),
+                _ => {}
+            }
+        }
+        summary
+    }
+}
+
+/// Opciones para parsear un mapa.
+/// # Attributes
+/// * `pad_short_rows` - Si es true, las filas mas cortas que la mas larga se completan con `Item::Empty` en lugar de producir un error.
+/// * `rules` - Reglas con las que se jugara el mapa.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ParseOptions {
+    pub pad_short_rows: bool,
+    pub rules: Rules,
+}
 
 impl fmt::Display for Map {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for line in &self.0 {
+        for line in &self.rows {
             let mut formatted_line = String::new();
             for item in line {
                 formatted_line.push_str(&(item.to_string() + " "));
@@ -21,11 +108,141 @@ impl fmt::Display for Map {
 
 impl Map {
     fn push_row(&mut self, line: Vec<Item>) {
-        self.0.push(line);
+        if self.rows.is_empty() {
+            self.width = line.len();
+        }
+        self.rows.push(line);
+        self.height = self.rows.len();
+    }
+
+    /// Completa las filas cortas con `Item::Empty` hasta alcanzar el ancho de la fila mas larga.
+    fn pad_rows(&mut self) {
+        self.width = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        for row in &mut self.rows {
+            row.resize_with(self.width, || Item::Empty);
+        }
+    }
+
+    /// Verifica que todas las filas tengan el mismo ancho que la primera.
+    /// # Arguments
+    /// * `row_number` - Traduce el indice de una fila al numero de fila a reportar en el error
+    /// # Returns
+    /// * `Result<(), Error>` - Error indicando la primera fila cuyo ancho no coincide con el de la primera fila
+    fn check_rectangular(&self, row_number: impl Fn(usize) -> usize) -> Result<(), Error> {
+        match self.rows.iter().position(|row| row.len() != self.width) {
+            Some(row_index) => Err(Error::RaggedRow {
+                row: row_number(row_index),
+                expected: self.width,
+                found: self.rows[row_index].len(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Crea un mapa a partir de sus filas, verificando que todas tengan el mismo ancho
+    /// # Arguments
+    /// * `rows` - Filas del mapa
+    /// # Returns
+    /// * `Result<Map, Error>` - Mapa creado o error indicando la primera fila con un ancho distinto a la primera
+    pub fn from_rows(rows: Vec<Vec<Item>>) -> Result<Map, Error> {
+        let mut map = Map::default();
+        for row in rows {
+            map.push_row(row);
+        }
+        map.check_rectangular(|row_index| row_index + 1)?;
+        map.check_teleporters()?;
+        Ok(map)
+    }
+
+    /// Verifica que cada teletransportador tenga exactamente una pareja y guarda las parejas
+    /// # Returns
+    /// * `Result<(), Error>` - Error indicando el primer teletransportador, por numero, que no tiene pareja o tiene mas de una
+    fn check_teleporters(&mut self) -> Result<(), Error> {
+        match unpaired_teleporters(self.rows.iter().flatten())
+            .into_iter()
+            .next()
+        {
+            Some(error) => Err(error),
+            None => {
+                self.pair_teleporters();
+                Ok(())
+            }
+        }
+    }
+
+    /// Calcula la pareja de cada teletransportador. Los numeros que no aparecen exactamente dos veces no forman parejas.
+    fn pair_teleporters(&mut self) {
+        let mut by_id: BTreeMap<u32, Vec<Point>> = BTreeMap::new();
+        for point in self.points() {
+            if let Item::Teleporter(id) = self.at(&point) {
+                by_id.entry(*id).or_default().push(point);
+            }
+        }
+        self.teleporters = by_id
+            .into_values()
+            .filter_map(|points| match points[..] {
+                [first, second] => Some([(first, second), (second, first)]),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+    }
+
+    /// Devuelve la pareja del teletransportador que se encuentra en un punto del mapa
+    /// # Arguments
+    /// * `point` - Punto del teletransportador
+    /// # Returns
+    /// * `Option<Point>` - Punto del otro teletransportador con el mismo numero, o None si el punto no contiene un teletransportador o no tiene pareja
+    pub fn teleporter_partner(&self, point: &Point) -> Option<Point> {
+        self.teleporters
+            .iter()
+            .find(|(teleporter, _)| teleporter == point)
+            .map(|(_, partner)| *partner)
+    }
+
+    /// Devuelve las reglas con las que se juega el mapa
+    pub fn rules(&self) -> &Rules {
+        &self.rules
     }
 
-    fn pop_row(&mut self) {
-        self.0.pop();
+    /// Cambia las reglas con las que se juega el mapa
+    /// # Arguments
+    /// * `rules` - Nuevas reglas
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
+    }
+
+    /// Devuelve la cantidad de columnas del mapa
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Devuelve la cantidad de filas del mapa
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Devuelve todos los puntos del mapa, recorriendo las filas de arriba hacia abajo y cada fila de izquierda a derecha
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.height).flat_map(move |y| (0..self.width).map(move |x| Point::new(x, y)))
+    }
+
+    /// Devuelve los puntos donde hay una bomba que puede detonarse, en el mismo orden que `points`
+    pub fn bombs(&self) -> Vec<Point> {
+        self.points()
+            .filter(|point| self.at(point).is_bomb())
+            .collect()
+    }
+
+    /// Cuenta los items del mapa que cumplen una condicion
+    /// # Arguments
+    /// * `predicate` - Condicion que deben cumplir los items
+    pub fn count_items(&self, predicate: impl Fn(&Item) -> bool) -> usize {
+        self.rows
+            .iter()
+            .flatten()
+            .filter(|item| predicate(item))
+            .count()
     }
 
     /// Verifica si un punto se encuentra dentro del mapa
@@ -34,7 +251,7 @@ impl Map {
     /// # Returns
     /// * `bool` - True si el punto se encuentra dentro del mapa, False en caso contrario
     pub fn is_point_in_map(&self, point: &Point) -> bool {
-        point.x < self.0[0].len() && point.y < self.0.len()
+        point.x < self.width && point.y < self.height
     }
 
     /// Devuelve el item que se encuentra en un punto del mapa
@@ -43,7 +260,7 @@ impl Map {
     /// # Returns
     /// * `&Item` - Item que se encuentra en el punto
     pub fn at(&self, point: &Point) -> &Item {
-        &self.0[point.y][point.x]
+        &self.rows[point.y][point.x]
     }
 
     /// Setea un item en un punto del mapa
@@ -51,36 +268,52 @@ impl Map {
     /// * `point` - Punto a verificar
     /// * `item` - Item a setear
     pub fn set_at(&mut self, point: &Point, item: Item) {
-        self.0[point.y][point.x] = item;
+        let before = std::mem::replace(&mut self.rows[point.y][point.x], item);
+        if matches!(before, Item::Teleporter(_)) || matches!(self.at(point), Item::Teleporter(_)) {
+            self.pair_teleporters();
+        }
+    }
+
+    /// Mueve el item de un punto del mapa a otro, dejando vacio el punto de origen y registrando ambas celdas
+    /// # Arguments
+    /// * `from` - Punto donde se encuentra el item
+    /// * `to` - Punto al que se mueve el item
+    /// * `record` - Registro al que se agregan los cambios
+    pub fn move_item_recorded(&mut self, from: &Point, to: &Point, record: &mut Detonation) {
+        let item = self.at(from).clone();
+        self.replace_at(from, Item::Empty, record);
+        self.replace_at(to, item, record);
     }
 
     /// Devuelve las propiedades de la bomba que se encuentra en un punto del mapa
     /// # Arguments
     /// * `point` - Punto a verificar
     /// # Returns
-    /// * `Result<(u32, bool), String>` - Tupla con el alcance y si es una bomba de traspaso o no, o un string con el mensaje de error.
-    pub fn get_explosion_properties(&self, point: &Point) -> Result<(u32, bool), String> {
+    /// * `Result<(u32, bool), Error>` - Tupla con el alcance y si es una bomba de traspaso o no, o el error en caso de que no haya una bomba.
+    pub fn get_explosion_properties(&self, point: &Point) -> Result<(u32, bool), Error> {
         let bomb = self.at(point);
 
         match bomb {
             Item::Bomb(reach) => Ok((*reach, false)),
             Item::PiercingBomb(reach) => Ok((*reach, true)),
-            _ => Err(String::from(
-                "ERROR: [Ocurrio un error durante la ejecucion].",
-            )),
+            Item::TimedBomb(reach, _) => Ok((*reach, false)),
+            Item::DiagonalBomb(reach) => Ok((*reach, false)),
+            _ => Err(Error::Execution),
         }
     }
 
-    fn get_next_point(&self, current: &Point, direction: &Direction) -> Option<Point> {
+    /// Devuelve el punto vecino en una direccion
+    /// # Arguments
+    /// * `current` - Punto de partida
+    /// * `direction` - Direccion en la que se avanza
+    /// # Returns
+    /// * `Option<Point>` - Punto vecino, o None si queda fuera del mapa
+    pub fn get_next_point(&self, current: &Point, direction: &Direction) -> Option<Point> {
         let x = current.x as isize;
         let y = current.y as isize;
+        let (dx, dy) = direction.offset();
 
-        let next_coordenates: (isize, isize) = match direction {
-            Direction::Up => (x, y - 1),
-            Direction::Down => (x, y + 1),
-            Direction::Left => (x - 1, y),
-            Direction::Right => (x + 1, y),
-        };
+        let next_coordenates: (isize, isize) = (x + dx, y + dy);
 
         if next_coordenates.0 < 0 || next_coordenates.1 < 0 {
             return None;
@@ -95,106 +328,726 @@ impl Map {
         Some(next_point)
     }
 
-    fn damage_enemies(&mut self, enemies_to_damage: HashMap<Point, u32>) {
-        for (enemy_point, enemy_health) in enemies_to_damage {
-            let new_health = enemy_health - 1;
+    /// Quita vida a los enemigos alcanzados, convirtiendo en `Item::Empty` a los que llegan a cero.
+    /// Por defecto el daño se descuenta de la vida que tenia cada enemigo al ser alcanzado, aunque otra bomba lo haya
+    /// dañado despues. Si las reglas indican `damage_current_health`, se descuenta de su vida actual y se ignoran los
+    /// enemigos que ya fueron eliminados.
+    /// # Arguments
+    /// * `enemies_to_damage` - Enemigos alcanzados, en el orden en que fueron alcanzados
+    fn damage_enemies(&mut self, enemies_to_damage: Vec<Hit>, record: &mut Detonation) {
+        for hit in enemies_to_damage {
+            let enemy_point = hit.point;
+            let health = if self.rules.damage_current_health {
+                match self.at(&enemy_point) {
+                    Item::Enemy(health) => *health,
+                    _ => continue,
+                }
+            } else {
+                hit.health
+            };
+            let new_health = health.saturating_sub(hit.damage);
             if new_health == 0 {
-                self.set_at(&enemy_point, Item::Empty);
+                self.replace_at(&enemy_point, Item::Empty, record);
+                record
+                    .events
+                    .push(ExplosionEvent::EnemyKilled { point: enemy_point });
             } else {
-                self.set_at(&enemy_point, Item::Enemy(new_health));
+                self.replace_at(&enemy_point, Item::Enemy(new_health), record);
+                record.events.push(ExplosionEvent::EnemyDamaged {
+                    point: enemy_point,
+                    health: new_health,
+                });
             }
         }
     }
 
+    /// Quita un punto de vida a la roca en un punto del mapa, convirtiendola en `Item::Empty` si llega a cero.
+    /// No hace nada si las reglas no permiten destruir rocas.
+    fn damage_rock(&mut self, point: &Point, record: &mut Detonation) {
+        if !self.rules.destructible_rocks {
+            return;
+        }
+        let health = match self.at(point) {
+            Item::Rock => 1,
+            Item::RockWithHealth(health) => *health,
+            _ => return,
+        };
+
+        if health > 1 {
+            self.replace_at(point, Item::RockWithHealth(health - 1), record);
+            record.events.push(ExplosionEvent::RockDamaged {
+                point: *point,
+                health: health - 1,
+            });
+        } else {
+            self.replace_at(point, Item::Empty, record);
+            record
+                .events
+                .push(ExplosionEvent::RockDestroyed { point: *point });
+        }
+    }
+
     /// Detona una bomba en un punto del mapa
     /// # Arguments
     /// * `point` - Punto donde se encuentra la bomba a detonar
     /// # Returns
-    /// * `Result<(), String>` - Resultado de la detonacion. No retorna nada en caso de exito o un mensaje de error en caso de que no se pueda detonar la bomba.
-    pub fn detonate_bomb(&mut self, point: &Point) -> Result<(), String> {
-        let (reach, is_piercing) = self.get_explosion_properties(point)?;
-        self.set_at(point, Item::Empty);
+    /// * `Result<(), Error>` - Resultado de la detonacion. No retorna nada en caso de exito o el error en caso de que no se pueda detonar la bomba.
+    pub fn detonate_bomb(&mut self, point: &Point) -> Result<(), Error> {
+        self.detonate_bomb_traced(point)?;
+        Ok(())
+    }
+
+    /// Detona una bomba en un punto del mapa, registrando todo lo que ocurre durante la explosion
+    /// # Arguments
+    /// * `point` - Punto donde se encuentra la bomba a detonar
+    /// # Returns
+    /// * `Result<Vec<ExplosionEvent>, Error>` - Eventos de la explosion en el orden en que ocurrieron, incluyendo los de las bombas encadenadas, o el error en caso de que no se pueda detonar la bomba.
+    pub fn detonate_bomb_traced(&mut self, point: &Point) -> Result<Vec<ExplosionEvent>, Error> {
+        Ok(self.detonate_bomb_recorded(point)?.events)
+    }
+
+    /// Detona una bomba en un punto del mapa, registrando los eventos de la explosion y cada celda que se modifico
+    /// # Arguments
+    /// * `point` - Punto donde se encuentra la bomba a detonar
+    /// # Returns
+    /// * `Result<Detonation, Error>` - Registro de la detonacion, o el error en caso de que no se pueda detonar la bomba.
+    pub fn detonate_bomb_recorded(&mut self, point: &Point) -> Result<Detonation, Error> {
+        let mut record = Detonation::default();
+        self.detonate(point, &mut record)?;
+        Ok(record)
+    }
+
+    /// Descuenta un turno del temporizador de cada bomba con temporizador. Las que llegan a cero explotan,
+    /// recorriendo el mapa por filas. Una bomba que ya exploto en cadena no vuelve a explotar.
+    /// # Returns
+    /// * `Result<Detonation, Error>` - Registro de los cambios de los temporizadores y de las explosiones producidas
+    pub fn tick_fuses(&mut self) -> Result<Detonation, Error> {
+        let mut record = Detonation::default();
+        let mut expired = Vec::new();
+
+        for point in self.points().collect::<Vec<Point>>() {
+            if let Item::TimedBomb(reach, fuse) = *self.at(&point) {
+                if fuse > 1 {
+                    self.replace_at(&point, Item::TimedBomb(reach, fuse - 1), &mut record);
+                } else {
+                    expired.push(point);
+                }
+            }
+        }
+
+        for point in expired {
+            if matches!(self.at(&point), Item::TimedBomb(..)) {
+                record.events.push(ExplosionEvent::FuseExpired { point });
+                self.detonate(&point, &mut record)?;
+            }
+        }
+        Ok(record)
+    }
+
+    /// Simula la detonacion de una bomba sin modificar el mapa
+    /// # Arguments
+    /// * `point` - Punto donde se encuentra la bomba a detonar
+    /// # Returns
+    /// * `Result<(Map, DetonationSummary), Error>` - Mapa resultante y resumen de la detonacion, o el error en caso de que no se pueda detonar la bomba.
+    pub fn preview_detonation(&self, point: &Point) -> Result<(Map, DetonationSummary), Error> {
+        let mut preview = self.clone();
+        let events = preview.detonate_bomb_traced(point)?;
+        Ok((preview, DetonationSummary::from_events(&events)))
+    }
 
-        self.spread_burst(point, Direction::Up, is_piercing, reach)?;
-        self.spread_burst(point, Direction::Down, is_piercing, reach)?;
-        self.spread_burst(point, Direction::Left, is_piercing, reach)?;
-        self.spread_burst(point, Direction::Right, is_piercing, reach)?;
+    fn replace_at(&mut self, point: &Point, item: Item, record: &mut Detonation) {
+        let before = self.at(point).clone();
+        self.set_at(point, item.clone());
+        record.changes.push(CellChange {
+            point: *point,
+            before,
+            after: item,
+        });
+    }
+
+    fn detonate(&mut self, point: &Point, record: &mut Detonation) -> Result<(), Error> {
+        let mut chain_hits = Vec::new();
+        self.detonate_in_chain(point, record, &mut chain_hits)?;
+        self.damage_enemies(chain_hits, record);
+        Ok(())
+    }
+
+    /// Detona una bomba que forma parte de una reaccion en cadena. El daño a los enemigos se aplica al terminar cada
+    /// rafaga, al terminar la bomba o se acumula en `chain_hits` para aplicarlo al terminar la cadena, segun las reglas.
+    fn detonate_in_chain(
+        &mut self,
+        point: &Point,
+        record: &mut Detonation,
+        chain_hits: &mut Vec<Hit>,
+    ) -> Result<(), Error> {
+        let (reach, is_piercing) = self.get_explosion_properties(point)?;
+        let bomb = self.at(point).clone();
+        self.replace_at(point, Item::Empty, record);
+        record.events.push(ExplosionEvent::BombDetonated {
+            point: *point,
+            reach,
+            piercing: is_piercing,
+        });
+
+        let damage = self.rules.bomb_damage.of(&bomb);
+        let mut bomb_hits = Vec::new();
+        for direction in blast_directions(&bomb) {
+            let enemies_hit =
+                self.spread_burst(point, direction, is_piercing, reach, record, chain_hits)?;
+            let hits = enemies_hit.into_iter().map(|(point, health)| Hit {
+                point,
+                health,
+                damage,
+            });
+            match self.rules.damage_policy {
+                DamagePolicy::PerBurst => self.damage_enemies(hits.collect(), record),
+                DamagePolicy::PerBomb => merge_hits(&mut bomb_hits, hits),
+                DamagePolicy::PerChain => merge_hits(chain_hits, hits),
+            }
+        }
+        self.damage_enemies(bomb_hits, record);
         Ok(())
     }
 
+    /// Propaga una rafaga desde un punto. Los divisores separan la rafaga en ramas, que se recorren una por una
+    /// compartiendo el alcance restante y los enemigos ya alcanzados. Una rama que vuelve a una celda con la misma
+    /// direccion termina y lo registra con `ExplosionEvent::LoopDetected`.
+    /// # Returns
+    /// * `Result<Vec<(Point, u32)>, Error>` - Enemigos alcanzados por la rafaga en el orden en que los alcanzo, que todavia no
+    ///   recibieron daño, junto con la vida que tenian al ser alcanzados
     fn spread_burst(
         &mut self,
         point: &Point,
         direction: Direction,
         is_piercing: bool,
         reach: u32,
-    ) -> Result<(), String> {
-        let mut current_point = *point;
-        let mut enemies_to_damage: HashMap<Point, u32> = HashMap::new();
-        let mut direction_to_use = &direction;
-
-        for _ in 0..=reach {
-            match self.at(&current_point) {
-                Item::Wall => break,
-                Item::Rock if !is_piercing => break,
-                Item::Bomb(_) | Item::PiercingBomb(_) => {
-                    self.detonate_bomb(&current_point)?;
-                    break;
+        record: &mut Detonation,
+        chain_hits: &mut Vec<Hit>,
+    ) -> Result<Vec<(Point, u32)>, Error> {
+        let mut enemies_hit: Vec<(Point, u32)> = Vec::new();
+        let mut walk = BurstWalk::new(self, point, direction, reach, is_piercing);
+
+        while let Some(step) = walk.step(self) {
+            let (current_point, distance, effect) = match step {
+                BurstStep::LoopDetected {
+                    point: current_point,
+                    direction,
+                } => {
+                    record.events.push(ExplosionEvent::LoopDetected {
+                        point: current_point,
+                        direction,
+                    });
+                    continue;
                 }
-                Item::Detour(detour_direction) => {
-                    direction_to_use = detour_direction;
+                BurstStep::BlockedByWall {
+                    point: current_point,
+                } => {
+                    record.events.push(ExplosionEvent::BlockedByWall {
+                        point: current_point,
+                    });
+                    continue;
                 }
-                Item::Enemy(enemy) if !enemies_to_damage.contains_key(&current_point) => {
-                    enemies_to_damage.insert(current_point, *enemy);
+                BurstStep::BlockedByRock {
+                    point: current_point,
+                } => {
+                    record.events.push(ExplosionEvent::BlockedByRock {
+                        point: current_point,
+                    });
+                    self.damage_rock(&current_point, record);
+                    continue;
                 }
-                _ => {}
-            };
-            current_point = match self.get_next_point(&current_point, direction_to_use) {
-                Some(p) => p,
-                None => break,
+                BurstStep::Burned {
+                    point: current_point,
+                    distance,
+                    effect,
+                } => (current_point, distance, effect),
             };
+            if distance > 0 {
+                record.events.push(ExplosionEvent::CellBurned {
+                    point: current_point,
+                });
+            }
+            match effect {
+                BurstEffect::ChainTriggered => {
+                    record.events.push(ExplosionEvent::ChainTriggered {
+                        from: *point,
+                        to: current_point,
+                        direction,
+                        distance,
+                    });
+                    self.detonate_in_chain(&current_point, record, chain_hits)?;
+                }
+                BurstEffect::Redirected(new_direction) => {
+                    record.events.push(ExplosionEvent::Redirected {
+                        point: current_point,
+                        direction: new_direction,
+                    });
+                }
+                BurstEffect::EnemyHit(health)
+                    if !enemies_hit.iter().any(|(enemy, _)| *enemy == current_point) =>
+                {
+                    enemies_hit.push((current_point, health));
+                }
+                BurstEffect::RockHit => self.damage_rock(&current_point, record),
+                BurstEffect::Teleported(partner) => {
+                    record.events.push(ExplosionEvent::Teleported {
+                        from: current_point,
+                        to: partner,
+                    });
+                }
+                BurstEffect::Reflected(reflected) => {
+                    record.events.push(ExplosionEvent::Reflected {
+                        point: current_point,
+                        direction: reflected,
+                    });
+                }
+                BurstEffect::Split(first, second) => {
+                    record.events.push(ExplosionEvent::Split {
+                        point: current_point,
+                        first,
+                        second,
+                    });
+                }
+                _ => {}
+            }
         }
-        self.damage_enemies(enemies_to_damage);
-        Ok(())
+        Ok(enemies_hit)
     }
 
-    /// Crea un mapa a partir de un archivo de entrada
+    /// Calcula las celdas que alcanzaria la explosion de una bomba, sin modificar el mapa ni detonar otras bombas.
+    /// La rafaga sigue los desvios, espejos y teletransportadores, se detiene en las paredes, en las rocas si la bomba
+    /// no es de traspaso y en las bombas que alcanza, salvo que las reglas indiquen lo contrario.
     /// # Arguments
-    /// * `input_file` - Nombre del archivo de entrada
+    /// * `point` - Punto donde se encuentra la bomba
     /// # Returns
-    /// * `Result<Map, String>` - Mapa creado o mensaje de error. El error se retorna unicamente en el caso donde un Item no puede ser parseado
-    pub fn new(input_file: &str) -> Result<Map, String> {
-        let map_raw = io::read_file(input_file)?;
-        let mut map = Map(Vec::new());
-
-        let lines = map_raw.split('\n');
-        for line in lines {
-            let mut matrix_row: Vec<Item> = Vec::new();
-            let values = line.split(' ');
-
-            for value in values {
-                if value.is_empty() {
-                    continue;
+    /// * `Result<HashSet<Point>, Error>` - Celdas alcanzadas, sin incluir la de la bomba, o el error en caso de que no haya una bomba en el punto
+    pub fn blast_area(&self, point: &Point) -> Result<HashSet<Point>, Error> {
+        let (reach, is_piercing) = self.get_explosion_properties(point)?;
+        let mut cells = HashSet::new();
+
+        for direction in blast_directions(self.at(point)) {
+            let mut walk = BurstWalk::new(self, point, direction, reach, is_piercing);
+            while let Some(step) = walk.step(self) {
+                if let BurstStep::Burned {
+                    point: current_point,
+                    distance,
+                    ..
+                } = step
+                {
+                    if distance > 0 {
+                        cells.insert(current_point);
+                    }
                 }
-                let item = Item::parse(value)?;
-                matrix_row.push(item);
             }
+        }
+        Ok(cells)
+    }
+
+    /// Crea un mapa a partir de un archivo de entrada
+    /// # Arguments
+    /// * `input_file` - Nombre del archivo de entrada
+    /// # Returns
+    /// * `Result<Map, Error>` - Mapa creado o error. El error se retorna en el caso donde el archivo no puede leerse o un Item no puede ser parseado
+    pub fn new(input_file: &str) -> Result<Map, Error> {
+        Map::new_with(input_file, &ParseOptions::default())
+    }
+
+    /// Crea un mapa a partir de un archivo de entrada usando las opciones indicadas
+    /// # Arguments
+    /// * `input_file` - Nombre del archivo de entrada
+    /// * `options` - Opciones de parseo, incluyendo las reglas con las que se jugara el mapa
+    /// # Returns
+    /// * `Result<Map, Error>` - Mapa creado o error. El error se retorna en el caso donde el archivo no puede leerse o un Item no puede ser parseado
+    pub fn new_with(input_file: &str, options: &ParseOptions) -> Result<Map, Error> {
+        Map::parse_with(&io::read_file(input_file)?, options)
+    }
+
+    /// Crea un mapa a partir de cualquier fuente que implemente `Read` (sockets, buffers en memoria, etc.)
+    /// # Arguments
+    /// * `reader` - Fuente de la que se lee el mapa
+    /// # Returns
+    /// * `Result<Map, Error>` - Mapa creado o error. El error se retorna en el caso donde la fuente no puede leerse o un Item no puede ser parseado
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Map, Error> {
+        let mut map_raw = String::new();
+        reader.read_to_string(&mut map_raw)?;
+        map_raw.parse()
+    }
+
+    /// Crea un mapa a partir de su representacion en texto usando las opciones indicadas
+    /// # Arguments
+    /// * `map_raw` - Texto con el mapa, una fila por linea y los items separados por espacios
+    /// * `options` - Opciones de parseo, por ejemplo si se permiten filas de distinto ancho
+    /// # Returns
+    /// * `Result<Map, Error>` - Mapa creado o error. El error se retorna en el caso donde un Item no puede ser parseado, las filas no tienen el mismo ancho o el texto no contiene ninguna fila
+    pub fn parse_with(map_raw: &str, options: &ParseOptions) -> Result<Map, Error> {
+        let mut map = Map {
+            rules: options.rules,
+            ..Map::default()
+        };
+        let mut line_numbers = Vec::new();
 
+        for (line_number, parsed_row) in parse_lines(map_raw, &options.rules) {
+            let matrix_row = parsed_row
+                .into_iter()
+                .collect::<Result<Vec<Item>, Error>>()?;
             map.push_row(matrix_row);
+            line_numbers.push(line_number);
+        }
+
+        if map.height == 0 {
+            return Err(Error::EmptyMap);
+        }
+
+        if options.pad_short_rows {
+            map.pad_rows();
         }
-        map.pop_row();
+        map.check_rectangular(|row_index| line_numbers[row_index])?;
+        map.check_teleporters()?;
 
         Ok(map)
     }
+
+    /// Recorre el texto completo de un mapa y devuelve todos los errores encontrados, en lugar de detenerse en el primero.
+    /// Ademas de los errores de cada token, reporta las filas con una cantidad de items distinta a la primera
+    /// y los teletransportadores que no tienen exactamente una pareja.
+    /// # Arguments
+    /// * `map_raw` - Texto con el mapa, una fila por linea y los items separados por espacios
+    /// # Returns
+    /// * `Vec<Error>` - Errores encontrados en el orden en que aparecen. Vacio si el mapa es valido
+    pub fn validate(map_raw: &str) -> Vec<Error> {
+        Map::validate_with(map_raw, &Rules::default())
+    }
+
+    /// Igual que `Map::validate`, pero validando los items con las reglas indicadas, por ejemplo la vida maxima de los enemigos.
+    /// # Arguments
+    /// * `map_raw` - Texto con el mapa, una fila por linea y los items separados por espacios
+    /// * `rules` - Reglas con las que se jugara el mapa
+    /// # Returns
+    /// * `Vec<Error>` - Errores encontrados en el orden en que aparecen. Vacio si el mapa es valido
+    pub fn validate_with(map_raw: &str, rules: &Rules) -> Vec<Error> {
+        let parsed_rows = parse_lines(map_raw, rules);
+        if parsed_rows.is_empty() {
+            return vec![Error::EmptyMap];
+        }
+
+        let mut errors = Vec::new();
+        let mut items = Vec::new();
+        let expected_width = parsed_rows[0].1.len();
+        for (line_number, parsed_row) in parsed_rows {
+            let width = parsed_row.len();
+            for parsed_item in parsed_row {
+                match parsed_item {
+                    Ok(item) => items.push(item),
+                    Err(error) => errors.push(error),
+                }
+            }
+
+            if width != expected_width {
+                errors.push(Error::RaggedRow {
+                    row: line_number,
+                    expected: expected_width,
+                    found: width,
+                });
+            }
+        }
+        errors.extend(unpaired_teleporters(items.iter()));
+
+        errors
+    }
+}
+
+impl FromStr for Map {
+    type Err = Error;
+
+    /// Crea un mapa a partir de su representacion en texto
+    /// # Arguments
+    /// * `map_raw` - Texto con el mapa, una fila por linea y los items separados por espacios
+    /// # Returns
+    /// * `Result<Map, Error>` - Mapa creado o error. El error se retorna en el caso donde un Item no puede ser parseado, e incluye la ubicacion del token, o si las filas no tienen el mismo ancho
+    fn from_str(map_raw: &str) -> Result<Map, Error> {
+        Map::parse_with(map_raw, &ParseOptions::default())
+    }
+}
+
+/// Parsea cada linea del texto de un mapa, sin detenerse ante los errores.
+/// Acepta saltos de linea `\n` o `\r\n`, con o sin salto de linea final, e ignora las lineas en blanco
+/// y los comentarios (lineas que comienzan con `#`).
+/// # Arguments
+/// * `map_raw` - Texto con el mapa
+/// * `rules` - Reglas con las que se validan los items
+/// # Returns
+/// * `Vec<(usize, Vec<Result<Item, Error>>)>` - Por cada fila del mapa, su numero de linea en el texto y el resultado de parsear cada uno de sus tokens. Los errores incluyen la ubicacion del token
+fn parse_lines(map_raw: &str, rules: &Rules) -> Vec<(usize, Vec<Result<Item, Error>>)> {
+    let mut rows = Vec::new();
+    let mut line_offset = 0;
+
+    for (line_index, line) in map_raw.split('\n').enumerate() {
+        let content = line.trim();
+        if !content.is_empty() && !content.starts_with('#') {
+            let row = tokenize_line(line, line_index + 1, line_offset)
+                .into_iter()
+                .map(|(value, position)| {
+                    Item::parse_with(value, rules).map_err(|e| e.at(value, position))
+                })
+                .collect();
+
+            rows.push((line_index + 1, row));
+        }
+        line_offset += line.len() + 1;
+    }
+
+    rows
+}
+
+/// Enemigo alcanzado por una explosion que todavia no recibio daño.
+/// # Attributes
+/// * `point` - Celda del enemigo.
+/// * `health` - Vida que tenia el enemigo al ser alcanzado.
+/// * `damage` - Daño que recibe.
+struct Hit {
+    point: Point,
+    health: u32,
+    damage: u32,
+}
+
+/// Agrega enemigos alcanzados a los que se dañaran juntos, conservando el orden en que fueron alcanzados por primera vez
+/// y la vida que tenian entonces. Un enemigo alcanzado mas de una vez recibe el mayor de los daños.
+fn merge_hits(enemies_to_damage: &mut Vec<Hit>, hits: impl Iterator<Item = Hit>) {
+    for hit in hits {
+        match enemies_to_damage
+            .iter_mut()
+            .find(|previous| previous.point == hit.point)
+        {
+            Some(previous) => previous.damage = previous.damage.max(hit.damage),
+            None => enemies_to_damage.push(hit),
+        }
+    }
+}
+
+/// Lo que ocurre cuando una rafaga llega a una celda
+enum BurstStep {
+    /// La rama ya habia llegado a la celda con la misma direccion y termina
+    LoopDetected { point: Point, direction: Direction },
+    /// Una pared detiene la rama
+    BlockedByWall { point: Point },
+    /// Una roca detiene la rama
+    BlockedByRock { point: Point },
+    /// La rafaga alcanza la celda despues de recorrer `distance` celdas desde la bomba
+    Burned {
+        point: Point,
+        distance: u32,
+        effect: BurstEffect,
+    },
+}
+
+/// Efecto del item de una celda alcanzada sobre la rafaga
+enum BurstEffect {
+    None,
+    /// La celda tiene una bomba que explota en cadena. La rama termina
+    ChainTriggered,
+    Redirected(Direction),
+    /// La celda tiene un enemigo con la vida indicada
+    EnemyHit(u32),
+    /// La celda tiene una roca que la rafaga atraviesa
+    RockHit,
+    /// La rafaga continua desde la pareja del teletransportador
+    Teleported(Point),
+    Reflected(Direction),
+    Split(Direction, Direction),
+}
+
+/// Recorrido de una rafaga por el mapa, que comparten la detonacion y el calculo del area de una bomba.
+/// Los divisores separan la rafaga en ramas, que se recorren una por una. Si la bomba sigue en su celda, como al
+/// calcular el area, la celda se recorre como si estuviera vacia, porque la bomba ya exploto.
+struct BurstWalk {
+    origin: Point,
+    reach: u32,
+    stops_at_rocks: bool,
+    bombs_chain: bool,
+    visited: HashMap<(Point, Direction), u32>,
+    branches: Vec<(Point, Direction, u32)>,
+}
+
+impl BurstWalk {
+    fn new(map: &Map, origin: &Point, direction: Direction, reach: u32, is_piercing: bool) -> Self {
+        BurstWalk {
+            origin: *origin,
+            reach,
+            stops_at_rocks: !is_piercing && map.rules.rocks_stop_bombs,
+            bombs_chain: map.rules.bombs_chain,
+            visited: HashMap::new(),
+            branches: vec![(*origin, direction, 0)],
+        }
+    }
+
+    fn push(&mut self, point: Point, direction: Direction, distance: u32) {
+        if distance <= self.reach {
+            self.branches.push((point, direction, distance));
+        }
+    }
+
+    /// Avanza la rafaga una celda. El mapa puede cambiar entre un paso y el siguiente, por ejemplo al dañar una roca o
+    /// detonar una bomba en cadena, y cada celda se lee recien cuando la rafaga llega a ella.
+    /// # Returns
+    /// * `Option<BurstStep>` - Lo que ocurrio en la celda alcanzada, o None si la rafaga termino
+    fn step(&mut self, map: &Map) -> Option<BurstStep> {
+        let (point, direction, distance) = self.branches.pop()?;
+        if !visit(&mut self.visited, point, direction, distance) {
+            return Some(BurstStep::LoopDetected { point, direction });
+        }
+        let item = match map.at(&point) {
+            item if point == self.origin && item.is_explosive() => &Item::Empty,
+            item => item,
+        };
+        match item {
+            Item::Wall => return Some(BurstStep::BlockedByWall { point }),
+            item if item.is_rock() && self.stops_at_rocks => {
+                return Some(BurstStep::BlockedByRock { point })
+            }
+            _ => {}
+        }
+
+        let mut next_from = point;
+        let mut next_direction = direction;
+        let effect = match item {
+            item if item.is_explosive() && self.bombs_chain => {
+                return Some(BurstStep::Burned {
+                    point,
+                    distance,
+                    effect: BurstEffect::ChainTriggered,
+                })
+            }
+            Item::Detour(detour_direction) => {
+                next_direction = *detour_direction;
+                BurstEffect::Redirected(next_direction)
+            }
+            Item::Enemy(health) => BurstEffect::EnemyHit(*health),
+            item if item.is_rock() => BurstEffect::RockHit,
+            Item::Teleporter(_) => match map.teleporter_partner(&point) {
+                Some(partner) => {
+                    next_from = partner;
+                    BurstEffect::Teleported(partner)
+                }
+                None => BurstEffect::None,
+            },
+            Item::Mirror(mirror) => match mirror.reflect(direction)[..] {
+                [reflected] => {
+                    next_direction = reflected;
+                    BurstEffect::Reflected(reflected)
+                }
+                [first, second] => {
+                    if let Some(next) = map.get_next_point(&point, &second) {
+                        self.push(next, second, distance + 1);
+                    }
+                    next_direction = first;
+                    BurstEffect::Split(first, second)
+                }
+                _ => {
+                    return Some(BurstStep::Burned {
+                        point,
+                        distance,
+                        effect: BurstEffect::None,
+                    })
+                }
+            },
+            _ => BurstEffect::None,
+        };
+        if let Some(next) = map.get_next_point(&next_from, &next_direction) {
+            self.push(next, next_direction, distance + 1);
+        }
+        Some(BurstStep::Burned {
+            point,
+            distance,
+            effect,
+        })
+    }
+}
+
+/// Registra que una rama de la rafaga llego a una celda con una direccion despues de recorrer `distance` celdas.
+/// Devuelve false si ya se habia llegado a ese mismo estado recorriendo a lo sumo esa distancia, porque continuar solo
+/// repetiria un recorrido ya hecho, como ocurre al dar vueltas en un ciclo de desvios, espejos o teletransportadores.
+fn visit(
+    visited: &mut HashMap<(Point, Direction), u32>,
+    point: Point,
+    direction: Direction,
+    distance: u32,
+) -> bool {
+    match visited.get(&(point, direction)) {
+        Some(previous) if *previous <= distance => false,
+        _ => {
+            visited.insert((point, direction), distance);
+            true
+        }
+    }
+}
+
+/// Devuelve las direcciones en las que se propaga la explosion de una bomba: las diagonales para las bombas diagonales
+/// y las ortogonales para el resto
+fn blast_directions(bomb: &Item) -> [Direction; 4] {
+    match bomb {
+        Item::DiagonalBomb(_) => Direction::DIAGONAL,
+        _ => Direction::ORTHOGONAL,
+    }
+}
+
+/// Cuenta los teletransportadores de cada numero y devuelve un error por cada numero que no aparece exactamente dos veces
+/// # Arguments
+/// * `items` - Items del mapa
+/// # Returns
+/// * `Vec<Error>` - Errores ordenados por numero de teletransportador
+fn unpaired_teleporters<'a>(items: impl Iterator<Item = &'a Item>) -> Vec<Error> {
+    let mut counts: BTreeMap<u32, usize> = BTreeMap::new();
+    for item in items {
+        if let Item::Teleporter(id) = item {
+            *counts.entry(*id).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count != 2)
+        .map(|(id, count)| Error::UnpairedTeleporter { id, count })
+        .collect()
+}
+
+/// Separa una linea del mapa en sus tokens, junto con la ubicacion de cada uno en el texto original
+/// # Arguments
+/// * `line` - Linea a separar
+/// * `row` - Numero de fila de la linea, comenzando en 1
+/// * `line_offset` - Posicion en bytes del comienzo de la linea dentro del texto
+/// # Returns
+/// * `Vec<(&str, Position)>` - Tokens de la linea con su ubicacion. Los tokens se separan por espacios, tabulaciones o `\r`
+fn tokenize_line(line: &str, row: usize, line_offset: usize) -> Vec<(&str, Position)> {
+    let mut tokens = Vec::new();
+    let mut token_start: Option<(usize, usize)> = None;
+
+    for (column_index, (byte_index, character)) in line.char_indices().enumerate() {
+        if !character.is_whitespace() {
+            token_start.get_or_insert((byte_index, column_index + 1));
+        } else if let Some((start, column)) = token_start.take() {
+            tokens.push((
+                &line[start..byte_index],
+                Position::new(row, column, line_offset + start),
+            ));
+        }
+    }
+    if let Some((start, column)) = token_start {
+        tokens.push((
+            &line[start..],
+            Position::new(row, column, line_offset + start),
+        ));
+    }
+
+    tokens
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rules::BombDamage;
 
     #[test]
     fn test_map_display() {
-        let mut map = Map(Vec::new());
+        let mut map = Map::default();
 
         assert!(format!("{}", map).is_empty());
 
@@ -207,44 +1060,53 @@ mod tests {
 
     #[test]
     fn test_map_push_row() {
-        let mut map = Map(Vec::new());
+        let mut map = Map::default();
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
 
-        assert_eq!(map.0.len(), 3);
-        assert_eq!(map.0[0].len(), 3);
-        assert_eq!(map.0[1].len(), 3);
-        assert_eq!(map.0[2].len(), 3);
+        assert_eq!(map.rows.len(), 3);
+        assert_eq!(map.rows[0].len(), 3);
+        assert_eq!(map.rows[1].len(), 3);
+        assert_eq!(map.rows[2].len(), 3);
+        assert_eq!(map.width(), 3);
+        assert_eq!(map.height(), 3);
     }
 
     #[test]
-    fn test_map_pop_row() {
-        let mut map = Map(Vec::new());
-        map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
+    fn test_map_is_point_on_map() {
+        let mut map = Map::default();
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
 
-        map.pop_row();
+        assert!(map.is_point_in_map(&Point::new(0, 0)));
+        assert!(!map.is_point_in_map(&Point::new(1, 3)));
 
-        assert_eq!(map.0.len(), 2);
-        assert_eq!(map.0[0].len(), 3);
-        assert_eq!(map.0[1].len(), 3);
+        assert!(!Map::default().is_point_in_map(&Point::new(0, 0)));
     }
 
     #[test]
-    fn test_map_is_point_on_map() {
-        let mut map = Map(Vec::new());
-        map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
-        map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
+    fn test_map_points_and_bombs() -> Result<(), Error> {
+        let map: Map = "B1 F1\nS2 R\n".parse()?;
 
-        assert!(map.is_point_in_map(&Point::new(0, 0)));
-        assert!(!map.is_point_in_map(&Point::new(1, 3)));
+        assert_eq!(
+            map.points().collect::<Vec<Point>>(),
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 0),
+                Point::new(0, 1),
+                Point::new(1, 1)
+            ]
+        );
+        assert_eq!(map.bombs(), vec![Point::new(0, 0), Point::new(0, 1)]);
+        assert_eq!(map.count_items(|item| matches!(item, Item::Enemy(_))), 1);
+
+        Ok(())
     }
 
     #[test]
     fn test_map_at() {
-        let mut map = Map(Vec::new());
+        let mut map = Map::default();
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Wall, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Rock]);
@@ -256,7 +1118,7 @@ mod tests {
 
     #[test]
     fn test_map_set_at() {
-        let mut map = Map(Vec::new());
+        let mut map = Map::default();
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
@@ -272,7 +1134,7 @@ mod tests {
 
     #[test]
     fn test_map_get_explosion_properties() {
-        let mut map = Map(Vec::new());
+        let mut map = Map::default();
         map.push_row(vec![Item::Bomb(1), Item::PiercingBomb(2), Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
@@ -291,7 +1153,7 @@ mod tests {
 
     #[test]
     fn test_map_get_next_point_on_edges() {
-        let mut map = Map(Vec::new());
+        let mut map = Map::default();
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
@@ -312,7 +1174,7 @@ mod tests {
     }
     #[test]
     fn test_map_get_next_point_on_center() {
-        let mut map = Map(Vec::new());
+        let mut map = Map::default();
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
@@ -335,64 +1197,104 @@ mod tests {
         );
     }
 
-    #[test]
-    fn test_map_damage_enemies() {
-        let mut map = Map(Vec::new());
-        map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
-        map.push_row(vec![Item::Empty, Item::Enemy(1), Item::Empty]);
-        map.push_row(vec![Item::Empty, Item::Enemy(2), Item::Empty]);
+    /// Propaga una rafaga y daña a los enemigos alcanzados como lo haria una bomba con las reglas por defecto.
+    fn spread_burst(
+        map: &mut Map,
+        point: &Point,
+        direction: Direction,
+        is_piercing: bool,
+        reach: u32,
+    ) -> Result<(), Error> {
+        let mut record = Detonation::default();
+        let enemies = map.spread_burst(
+            point,
+            direction,
+            is_piercing,
+            reach,
+            &mut record,
+            &mut Vec::new(),
+        )?;
+        map.damage_enemies(
+            enemies
+                .into_iter()
+                .map(|(point, health)| Hit {
+                    point,
+                    health,
+                    damage: 1,
+                })
+                .collect(),
+            &mut record,
+        );
+        Ok(())
+    }
 
-        let mut enemies_to_damage: HashMap<Point, u32> = HashMap::new();
-        enemies_to_damage.insert(Point::new(1, 1), 1);
-        enemies_to_damage.insert(Point::new(1, 2), 2);
+    #[test]
+    fn test_map_damage_enemies() {
+        let mut map = Map::default();
+        map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
+        map.push_row(vec![Item::Empty, Item::Enemy(1), Item::Empty]);
+        map.push_row(vec![Item::Empty, Item::Enemy(2), Item::Empty]);
 
-        map.damage_enemies(enemies_to_damage);
+        let enemies_to_damage = vec![
+            Hit {
+                point: Point::new(1, 1),
+                health: 1,
+                damage: 1,
+            },
+            Hit {
+                point: Point::new(1, 2),
+                health: 2,
+                damage: 1,
+            },
+        ];
+
+        map.damage_enemies(enemies_to_damage, &mut Detonation::default());
 
         assert_eq!(map.at(&Point::new(1, 1)), &Item::Empty);
         assert_eq!(map.at(&Point::new(1, 2)), &Item::Enemy(1));
     }
 
     #[test]
-    fn test_map_spread_burst_simple() -> Result<(), String> {
-        let mut map = Map(Vec::new());
+    fn test_map_spread_burst_simple() -> Result<(), Error> {
+        let mut map = Map::default();
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Enemy(1), Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Enemy(2), Item::Enemy(2)]);
 
-        map.spread_burst(&Point::new(1, 1), Direction::Up, false, 1)?;
+        spread_burst(&mut map, &Point::new(1, 1), Direction::Up, false, 1)?;
         assert_eq!(map.at(&Point::new(1, 1)), &Item::Empty);
         assert_eq!(map.at(&Point::new(1, 2)), &Item::Enemy(2));
 
-        map.spread_burst(&Point::new(1, 2), Direction::Up, false, 1)?;
+        spread_burst(&mut map, &Point::new(1, 2), Direction::Up, false, 1)?;
         assert_eq!(map.at(&Point::new(1, 2)), &Item::Enemy(1));
 
-        map.spread_burst(&Point::new(0, 2), Direction::Right, false, 1)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Right, false, 1)?;
         assert_eq!(map.at(&Point::new(2, 2)), &Item::Enemy(2));
 
-        map.spread_burst(&Point::new(0, 2), Direction::Right, false, 2)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Right, false, 2)?;
         assert_eq!(map.at(&Point::new(2, 2)), &Item::Enemy(1));
 
-        map.spread_burst(&Point::new(0, 2), Direction::Right, true, 2)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Right, true, 2)?;
         assert_eq!(map.at(&Point::new(2, 2)), &Item::Empty);
 
         Ok(())
     }
 
     #[test]
-    fn test_map_spread_burst_with_walls() -> Result<(), String> {
-        let mut map = Map(Vec::new());
+    fn test_map_spread_burst_with_walls() -> Result<(), Error> {
+        let mut map = Map::default();
         map.push_row(vec![Item::Enemy(1), Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Wall, Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
 
-        map.spread_burst(&Point::new(0, 2), Direction::Up, false, 1)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Up, false, 1)?;
         assert_eq!(map.at(&Point::new(0, 1)), &Item::Wall);
 
-        map.spread_burst(&Point::new(0, 2), Direction::Up, false, 2)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Up, false, 2)?;
         assert_eq!(map.at(&Point::new(0, 1)), &Item::Wall);
         assert_eq!(map.at(&Point::new(0, 0)), &Item::Enemy(1));
 
-        map.spread_burst(&Point::new(0, 2), Direction::Up, true, 1)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Up, true, 1)?;
         assert_eq!(map.at(&Point::new(0, 1)), &Item::Wall);
         assert_eq!(map.at(&Point::new(0, 0)), &Item::Enemy(1));
 
@@ -400,24 +1302,24 @@ mod tests {
     }
 
     #[test]
-    fn test_map_spread_burst_with_rocks() -> Result<(), String> {
-        let mut map = Map(Vec::new());
+    fn test_map_spread_burst_with_rocks() -> Result<(), Error> {
+        let mut map = Map::default();
         map.push_row(vec![Item::Enemy(1), Item::Enemy(1), Item::Empty]);
         map.push_row(vec![Item::Rock, Item::Rock, Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
 
-        map.spread_burst(&Point::new(0, 2), Direction::Up, false, 1)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Up, false, 1)?;
         assert_eq!(map.at(&Point::new(0, 1)), &Item::Rock);
 
-        map.spread_burst(&Point::new(0, 2), Direction::Up, false, 2)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Up, false, 2)?;
         assert_eq!(map.at(&Point::new(0, 1)), &Item::Rock);
         assert_eq!(map.at(&Point::new(0, 0)), &Item::Enemy(1));
 
-        map.spread_burst(&Point::new(0, 2), Direction::Up, true, 1)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Up, true, 1)?;
         assert_eq!(map.at(&Point::new(0, 1)), &Item::Rock);
         assert_eq!(map.at(&Point::new(0, 0)), &Item::Enemy(1));
 
-        map.spread_burst(&Point::new(1, 2), Direction::Up, true, 2)?;
+        spread_burst(&mut map, &Point::new(1, 2), Direction::Up, true, 2)?;
         assert_eq!(map.at(&Point::new(1, 1)), &Item::Rock);
         assert_eq!(map.at(&Point::new(1, 0)), &Item::Empty);
 
@@ -425,8 +1327,8 @@ mod tests {
     }
 
     #[test]
-    fn test_map_spread_burst_with_detours() -> Result<(), String> {
-        let mut map = Map(Vec::new());
+    fn test_map_spread_burst_with_detours() -> Result<(), Error> {
+        let mut map = Map::default();
         map.push_row(vec![Item::Enemy(1), Item::Empty, Item::Empty]);
         map.push_row(vec![
             Item::Detour(Direction::Right),
@@ -439,32 +1341,32 @@ mod tests {
             Item::Detour(Direction::Left),
         ]);
 
-        map.spread_burst(&Point::new(0, 2), Direction::Up, false, 1)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Up, false, 1)?;
         assert_eq!(map.at(&Point::new(0, 1)), &Item::Detour(Direction::Right));
         assert_eq!(map.at(&Point::new(0, 0)), &Item::Enemy(1));
         assert_eq!(map.at(&Point::new(1, 1)), &Item::Enemy(1));
 
-        map.spread_burst(&Point::new(0, 2), Direction::Up, false, 2)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Up, false, 2)?;
         assert_eq!(map.at(&Point::new(0, 0)), &Item::Enemy(1));
         assert_eq!(map.at(&Point::new(1, 1)), &Item::Empty);
 
-        map.spread_burst(&Point::new(0, 2), Direction::Right, true, 2)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Right, true, 2)?;
         assert_eq!(map.at(&Point::new(1, 2)), &Item::Enemy(2));
 
-        map.spread_burst(&Point::new(0, 2), Direction::Right, true, 3)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Right, true, 3)?;
         assert_eq!(map.at(&Point::new(1, 2)), &Item::Enemy(1));
 
         Ok(())
     }
 
     #[test]
-    fn test_map_spread_burst_with_bombs() -> Result<(), String> {
-        let mut map = Map(Vec::new());
+    fn test_map_spread_burst_with_bombs() -> Result<(), Error> {
+        let mut map = Map::default();
         map.push_row(vec![Item::Enemy(1), Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Bomb(1), Item::Enemy(1), Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
 
-        map.spread_burst(&Point::new(0, 2), Direction::Up, false, 1)?;
+        spread_burst(&mut map, &Point::new(0, 2), Direction::Up, false, 1)?;
         assert_eq!(map.at(&Point::new(0, 1)), &Item::Empty);
         assert_eq!(map.at(&Point::new(0, 0)), &Item::Empty);
         assert_eq!(map.at(&Point::new(1, 1)), &Item::Empty);
@@ -473,8 +1375,8 @@ mod tests {
     }
 
     #[test]
-    fn test_map_detonate_bomb() -> Result<(), String> {
-        let mut map = Map(Vec::new());
+    fn test_map_detonate_bomb() -> Result<(), Error> {
+        let mut map = Map::default();
         map.push_row(vec![Item::Enemy(1), Item::Empty, Item::Empty]);
         map.push_row(vec![Item::Bomb(1), Item::Enemy(1), Item::Empty]);
         map.push_row(vec![Item::Empty, Item::Empty, Item::Empty]);
@@ -484,16 +1386,683 @@ mod tests {
         assert_eq!(map.at(&Point::new(0, 0)), &Item::Empty);
         assert_eq!(map.at(&Point::new(1, 1)), &Item::Empty);
 
-        match map.detonate_bomb(&Point::new(0, 1)) {
-            Ok(_) => assert!(false),
-            Err(err) => assert_eq!(err, "ERROR: [Ocurrio un error durante la ejecucion]."),
-        }
+        assert_eq!(map.detonate_bomb(&Point::new(0, 1)), Err(Error::Execution));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_detonation_damage_order() -> Result<(), Error> {
+        let mut map: Map = "B3 DD F2\nF2 F2 F2\nF2 _ _\n".parse()?;
+        let events = map.detonate_bomb_traced(&Point::new(0, 0))?;
+
+        assert_eq!(
+            events,
+            vec![
+                ExplosionEvent::BombDetonated {
+                    point: Point::new(0, 0),
+                    reach: 3,
+                    piercing: false
+                },
+                ExplosionEvent::CellBurned {
+                    point: Point::new(0, 1)
+                },
+                ExplosionEvent::CellBurned {
+                    point: Point::new(0, 2)
+                },
+                ExplosionEvent::EnemyDamaged {
+                    point: Point::new(0, 1),
+                    health: 1
+                },
+                ExplosionEvent::EnemyDamaged {
+                    point: Point::new(0, 2),
+                    health: 1
+                },
+                ExplosionEvent::CellBurned {
+                    point: Point::new(1, 0)
+                },
+                ExplosionEvent::Redirected {
+                    point: Point::new(1, 0),
+                    direction: Direction::Down
+                },
+                ExplosionEvent::CellBurned {
+                    point: Point::new(1, 1)
+                },
+                ExplosionEvent::CellBurned {
+                    point: Point::new(1, 2)
+                },
+                ExplosionEvent::EnemyDamaged {
+                    point: Point::new(1, 1),
+                    health: 1
+                },
+            ]
+        );
+        assert_eq!(map.to_string(), "_ DD F2\nF1 F1 F2\nF1 _ _\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_detonate_bomb_traced() -> Result<(), Error> {
+        let mut map = Map::default();
+        map.push_row(vec![Item::Wall, Item::Empty, Item::Empty]);
+        map.push_row(vec![Item::Bomb(1), Item::Enemy(2), Item::Rock]);
+        map.push_row(vec![
+            Item::Bomb(2),
+            Item::Detour(Direction::Up),
+            Item::Empty,
+        ]);
+
+        let events = map.detonate_bomb_traced(&Point::new(0, 1))?;
+
+        assert_eq!(
+            events,
+            vec![
+                ExplosionEvent::BombDetonated {
+                    point: Point::new(0, 1),
+                    reach: 1,
+                    piercing: false
+                },
+                ExplosionEvent::BlockedByWall {
+                    point: Point::new(0, 0)
+                },
+                ExplosionEvent::CellBurned {
+                    point: Point::new(0, 2)
+                },
+                ExplosionEvent::ChainTriggered {
+                    from: Point::new(0, 1),
+                    to: Point::new(0, 2),
+                    direction: Direction::Down,
+                    distance: 1
+                },
+                ExplosionEvent::BombDetonated {
+                    point: Point::new(0, 2),
+                    reach: 2,
+                    piercing: false
+                },
+                ExplosionEvent::CellBurned {
+                    point: Point::new(0, 1)
+                },
+                ExplosionEvent::BlockedByWall {
+                    point: Point::new(0, 0)
+                },
+                ExplosionEvent::CellBurned {
+                    point: Point::new(1, 2)
+                },
+                ExplosionEvent::Redirected {
+                    point: Point::new(1, 2),
+                    direction: Direction::Up
+                },
+                ExplosionEvent::CellBurned {
+                    point: Point::new(1, 1)
+                },
+                ExplosionEvent::EnemyDamaged {
+                    point: Point::new(1, 1),
+                    health: 1
+                },
+                ExplosionEvent::CellBurned {
+                    point: Point::new(1, 1)
+                },
+                ExplosionEvent::EnemyKilled {
+                    point: Point::new(1, 1)
+                },
+            ]
+        );
+        assert_eq!(map.at(&Point::new(1, 1)), &Item::Empty);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_detonate_bomb_recorded() -> Result<(), Error> {
+        let mut map = Map::default();
+        map.push_row(vec![Item::Bomb(1), Item::Enemy(2), Item::Empty]);
+
+        let record = map.detonate_bomb_recorded(&Point::new(0, 0))?;
+
+        assert_eq!(
+            record.changes,
+            vec![
+                CellChange {
+                    point: Point::new(0, 0),
+                    before: Item::Bomb(1),
+                    after: Item::Empty
+                },
+                CellChange {
+                    point: Point::new(1, 0),
+                    before: Item::Enemy(2),
+                    after: Item::Enemy(1)
+                },
+            ]
+        );
+        assert_eq!(record.events.len(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_damage_policy() -> Result<(), Error> {
+        let detonate_with = |input: &str, rules| -> Result<String, Error> {
+            let mut map: Map = input.parse()?;
+            map.set_rules(rules);
+            map.detonate_bomb(&Point::new(0, 0))?;
+            Ok(map.to_string())
+        };
+        let detonate = |input: &str, damage_policy| {
+            detonate_with(
+                input,
+                Rules {
+                    damage_policy,
+                    damage_current_health: true,
+                    ..Rules::default()
+                },
+            )
+        };
+
+        // El enemigo es alcanzado por dos rafagas de la misma bomba.
+        assert_eq!(
+            detonate("B2 DD\nDR F3\n", DamagePolicy::PerBurst)?,
+            "_ DD\nDR F1\n"
+        );
+        assert_eq!(
+            detonate("B2 DD\nDR F3\n", DamagePolicy::PerBomb)?,
+            "_ DD\nDR F2\n"
+        );
+        assert_eq!(
+            detonate("B2 DD\nDR F3\n", DamagePolicy::PerChain)?,
+            "_ DD\nDR F2\n"
+        );
+
+        // El enemigo es alcanzado por dos bombas de la misma cadena.
+        assert_eq!(detonate("B2 F3 B1\n", DamagePolicy::PerBomb)?, "_ F1 _\n");
+        assert_eq!(detonate("B2 F3 B1\n", DamagePolicy::PerChain)?, "_ F2 _\n");
+
+        assert_eq!(detonate("B3 F2 B1\n", DamagePolicy::PerBurst)?, "_ _ _\n");
+
+        // Con las reglas por defecto el daño se descuenta de la vida que tenia el enemigo al ser alcanzado, por lo que
+        // la rafaga que lo alcanzo primero termina despues y reemplaza el daño de la bomba encadenada.
+        assert_eq!(detonate_with("B3 F2 B1\n", Rules::default())?, "_ F1 _\n");
+        assert_eq!(
+            detonate_with("B2\nF3\nB3\nR\nF1\n", Rules::default())?,
+            "_\nF2\n_\nR\nF1\n"
+        );
+
+        // Los enemigos se dañan en el orden en que fueron alcanzados: con `PerBomb` primero los de la bomba encadenada,
+        // que termina antes, y con `PerChain` en el orden en que la cadena los alcanzo por primera vez.
+        let killed = |damage_policy| -> Result<Vec<Point>, Error> {
+            let mut map: Map = "F1 _ F1\nB1 B2 F1\nF1 _ F1\n".parse()?;
+            map.set_rules(Rules {
+                damage_policy,
+                ..Rules::default()
+            });
+            Ok(map
+                .detonate_bomb_traced(&Point::new(0, 1))?
+                .into_iter()
+                .filter_map(|event| match event {
+                    ExplosionEvent::EnemyKilled { point } => Some(point),
+                    _ => None,
+                })
+                .collect())
+        };
+        assert_eq!(
+            killed(DamagePolicy::PerBomb)?,
+            vec![Point::new(2, 1), Point::new(0, 0), Point::new(0, 2)]
+        );
+        assert_eq!(
+            killed(DamagePolicy::PerChain)?,
+            vec![Point::new(0, 0), Point::new(0, 2), Point::new(2, 1)]
+        );
 
         Ok(())
     }
 
     #[test]
-    fn test_map_parse() -> Result<(), String> {
+    fn test_map_bomb_damage() -> Result<(), Error> {
+        let detonate = |damage_policy| -> Result<String, Error> {
+            let mut map: Map = "B2 F3 S1\n".parse()?;
+            map.set_rules(Rules {
+                damage_policy,
+                bomb_damage: BombDamage {
+                    piercing_bomb: 2,
+                    ..BombDamage::default()
+                },
+                damage_current_health: true,
+                ..Rules::default()
+            });
+            map.detonate_bomb(&Point::new(0, 0))?;
+            Ok(map.to_string())
+        };
+
+        assert_eq!(detonate(DamagePolicy::PerBurst)?, "_ _ _\n");
+        // Con una sola aplicacion por cadena, el enemigo recibe el mayor de los daños.
+        assert_eq!(detonate(DamagePolicy::PerChain)?, "_ F1 _\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_destructible_rocks() -> Result<(), Error> {
+        let options = ParseOptions {
+            rules: Rules {
+                destructible_rocks: true,
+                ..Rules::default()
+            },
+            ..ParseOptions::default()
+        };
+
+        let mut map = Map::parse_with("B3 R2 F1\n", &options)?;
+        let events = map.detonate_bomb_traced(&Point::new(0, 0))?;
+        assert_eq!(map.to_string(), "_ R1 F1\n");
+        assert!(events.contains(&ExplosionEvent::RockDamaged {
+            point: Point::new(1, 0),
+            health: 1
+        }));
+
+        let mut map = Map::parse_with("S3 R _ F1\n", &options)?;
+        let events = map.detonate_bomb_traced(&Point::new(0, 0))?;
+        assert_eq!(map.to_string(), "_ _ _ _\n");
+        assert!(events.contains(&ExplosionEvent::RockDestroyed {
+            point: Point::new(1, 0)
+        }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_rocks_default_rules() -> Result<(), Error> {
+        let mut map: Map = "B3 R2 F1\n".parse()?;
+        map.detonate_bomb(&Point::new(0, 0))?;
+        assert_eq!(map.to_string(), "_ R2 F1\n");
+
+        let mut map: Map = "S3 R _ F1\n".parse()?;
+        map.detonate_bomb(&Point::new(0, 0))?;
+        assert_eq!(map.to_string(), "_ R _ _\n");
+        assert_eq!(map.rules(), &Rules::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_rocks_do_not_stop_bombs() -> Result<(), Error> {
+        let options = ParseOptions {
+            rules: Rules {
+                rocks_stop_bombs: false,
+                ..Rules::default()
+            },
+            ..ParseOptions::default()
+        };
+        let mut map = Map::parse_with("B3 R _ F1\n", &options)?;
+
+        assert_eq!(map.blast_area(&Point::new(0, 0))?.len(), 3);
+        map.detonate_bomb(&Point::new(0, 0))?;
+        assert_eq!(map.to_string(), "_ R _ _\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_bombs_do_not_chain() -> Result<(), Error> {
+        let options = ParseOptions {
+            rules: Rules {
+                bombs_chain: false,
+                ..Rules::default()
+            },
+            ..ParseOptions::default()
+        };
+        let mut map = Map::parse_with("B3 B1 F1 F1\n", &options)?;
+
+        assert_eq!(map.blast_area(&Point::new(0, 0))?.len(), 3);
+        let events = map.detonate_bomb_traced(&Point::new(0, 0))?;
+        assert!(DetonationSummary::from_events(&events)
+            .bombs_chained
+            .is_empty());
+        assert_eq!(map.to_string(), "_ B1 _ _\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_parse_max_enemy_health() -> Result<(), Error> {
+        let rules = Rules {
+            max_enemy_health: 5,
+            ..Rules::default()
+        };
+        let options = ParseOptions {
+            rules,
+            ..ParseOptions::default()
+        };
+
+        assert_eq!(Map::parse_with("F5 B1\n", &options)?.rules(), &rules);
+        assert!(Map::validate_with("F5 F6\n", &rules)
+            .iter()
+            .any(|error| error.kind() == &Error::EnemyHealthOverLimit { health: 6, max: 5 }));
+        assert_eq!(Map::validate("F5\n").len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_teleporters() -> Result<(), Error> {
+        let mut map = Map::new("test_dir/map_with_teleporters.txt")?;
+
+        assert_eq!(
+            map.teleporter_partner(&Point::new(1, 0)),
+            Some(Point::new(2, 2))
+        );
+        assert_eq!(map.teleporter_partner(&Point::new(0, 1)), None);
+        assert!(map
+            .blast_area(&Point::new(0, 0))?
+            .contains(&Point::new(3, 2)));
+
+        let events = map.detonate_bomb_traced(&Point::new(0, 0))?;
+        assert!(events.contains(&ExplosionEvent::Teleported {
+            from: Point::new(1, 0),
+            to: Point::new(2, 2)
+        }));
+        assert_eq!(map.to_string(), "_ P1 _ W\n_ W _ _\n_ _ P1 _\n");
+
+        map.set_at(&Point::new(2, 2), Item::Empty);
+        assert_eq!(map.teleporter_partner(&Point::new(1, 0)), None);
+        map.set_at(&Point::new(0, 2), Item::Teleporter(1));
+        assert_eq!(
+            map.teleporter_partner(&Point::new(1, 0)),
+            Some(Point::new(0, 2))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_parse_unpaired_teleporters() {
+        assert_eq!(
+            "P1 _ P2\n_ P1 _\n".parse::<Map>().err(),
+            Some(Error::UnpairedTeleporter { id: 2, count: 1 })
+        );
+        assert_eq!(
+            Map::from_rows(vec![vec![Item::Teleporter(3)]]).err(),
+            Some(Error::UnpairedTeleporter { id: 3, count: 1 })
+        );
+    }
+
+    #[test]
+    fn test_map_get_next_point_diagonal() {
+        let mut map = Map::default();
+        map.push_row(vec![Item::Empty, Item::Empty]);
+        map.push_row(vec![Item::Empty, Item::Empty]);
+
+        assert_eq!(
+            map.get_next_point(&Point::new(0, 0), &Direction::DownRight),
+            Some(Point::new(1, 1))
+        );
+        assert_eq!(
+            map.get_next_point(&Point::new(1, 0), &Direction::DownLeft),
+            Some(Point::new(0, 1))
+        );
+        assert_eq!(
+            map.get_next_point(&Point::new(0, 1), &Direction::UpLeft),
+            None
+        );
+        assert_eq!(
+            map.get_next_point(&Point::new(1, 1), &Direction::DownRight),
+            None
+        );
+    }
+
+    #[test]
+    fn test_map_diagonal_bomb() -> Result<(), Error> {
+        let mut map: Map = "X2 _ F1\n_ _ _\nF2 _ F1\n".parse()?;
+
+        assert_eq!(
+            map.blast_area(&Point::new(0, 0))?,
+            HashSet::from([Point::new(1, 1), Point::new(2, 2)])
+        );
+        map.detonate_bomb(&Point::new(0, 0))?;
+        assert_eq!(map.to_string(), "_ _ F1\n_ _ _\nF2 _ _\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_diagonal_detour() -> Result<(), Error> {
+        let mut map: Map = "B2 DDR _\n_ _ F1\n".parse()?;
+        map.detonate_bomb(&Point::new(0, 0))?;
+
+        assert_eq!(map.to_string(), "_ DDR _\n_ _ _\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_mirrors() -> Result<(), Error> {
+        let mut map: Map = "_ _ F1\n_ _ _\nB4 _ /\n".parse()?;
+        map.detonate_bomb(&Point::new(0, 2))?;
+        assert_eq!(map.to_string(), "_ _ _\n_ _ _\n_ _ /\n");
+
+        let mut map: Map = "B3 _ \\\n_ _ F1\n".parse()?;
+        let events = map.detonate_bomb_traced(&Point::new(0, 0))?;
+        assert!(events.contains(&ExplosionEvent::Reflected {
+            point: Point::new(2, 0),
+            direction: Direction::Down
+        }));
+        assert_eq!(map.to_string(), "_ _ \\\n_ _ _\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_splitter() -> Result<(), Error> {
+        let mut map: Map = "_ F1 _\nB2 Y F1\n_ F1 _\n".parse()?;
+        let events = map.detonate_bomb_traced(&Point::new(0, 1))?;
+
+        assert!(events.contains(&ExplosionEvent::Split {
+            point: Point::new(1, 1),
+            first: Direction::Down,
+            second: Direction::Up
+        }));
+        assert_eq!(map.to_string(), "_ _ _\n_ Y F1\n_ _ _\n");
+
+        let map: Map = "_ F1 _\nB2 Y F1\n_ F1 _\n".parse()?;
+        let area = map.blast_area(&Point::new(0, 1))?;
+        assert_eq!(area.len(), 5);
+        assert!(!area.contains(&Point::new(2, 1)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_detour_cycle_ends() -> Result<(), Error> {
+        let mut map: Map = "B4294967295 DD DL\n_ DR DU\n".parse()?;
+        let area = map.blast_area(&Point::new(0, 0))?;
+        let events = map.detonate_bomb_traced(&Point::new(0, 0))?;
+
+        assert_eq!(area.len(), 5);
+        assert!(events.contains(&ExplosionEvent::LoopDetected {
+            point: Point::new(1, 1),
+            direction: Direction::Down
+        }));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|event| matches!(event, ExplosionEvent::Redirected { .. }))
+                .count(),
+            5
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_mirror_cycle_ends() -> Result<(), Error> {
+        let mut map: Map = "_ / F1 \\\nB99 Y _ _\n_ \\ _ /\n".parse()?;
+        let area = map.blast_area(&Point::new(0, 1))?;
+        let events = map.detonate_bomb_traced(&Point::new(0, 1))?;
+
+        let mirror_events: Vec<ExplosionEvent> = events
+            .into_iter()
+            .filter(|event| {
+                matches!(
+                    event,
+                    ExplosionEvent::Split { .. }
+                        | ExplosionEvent::Reflected { .. }
+                        | ExplosionEvent::LoopDetected { .. }
+                        | ExplosionEvent::EnemyKilled { .. }
+                )
+            })
+            .collect();
+        let split = |x, y, first, second| ExplosionEvent::Split {
+            point: Point::new(x, y),
+            first,
+            second,
+        };
+        let reflected = |x, y, direction| ExplosionEvent::Reflected {
+            point: Point::new(x, y),
+            direction,
+        };
+
+        // Cada rama recorre el ciclo de espejos una vez y termina al volver a una celda con la misma direccion.
+        assert_eq!(
+            mirror_events,
+            vec![
+                split(1, 1, Direction::Down, Direction::Up),
+                reflected(1, 2, Direction::Right),
+                reflected(3, 2, Direction::Up),
+                reflected(3, 0, Direction::Left),
+                reflected(1, 0, Direction::Down),
+                split(1, 1, Direction::Left, Direction::Right),
+                reflected(1, 0, Direction::Right),
+                reflected(3, 0, Direction::Down),
+                reflected(3, 2, Direction::Left),
+                reflected(1, 2, Direction::Up),
+                split(1, 1, Direction::Right, Direction::Left),
+                ExplosionEvent::LoopDetected {
+                    point: Point::new(2, 1),
+                    direction: Direction::Right
+                },
+                ExplosionEvent::LoopDetected {
+                    point: Point::new(0, 1),
+                    direction: Direction::Left
+                },
+                ExplosionEvent::EnemyKilled {
+                    point: Point::new(2, 0)
+                },
+            ]
+        );
+        assert_eq!(map.at(&Point::new(2, 0)), &Item::Empty);
+        assert!(area.contains(&Point::new(2, 0)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_tick_fuses() -> Result<(), Error> {
+        let mut map: Map = "T1:2 F1\n".parse()?;
+
+        let record = map.tick_fuses()?;
+        assert!(record.events.is_empty());
+        assert_eq!(map.to_string(), "T1:1 F1\n");
+
+        let record = map.tick_fuses()?;
+        assert_eq!(
+            record.events[..2],
+            [
+                ExplosionEvent::FuseExpired {
+                    point: Point::new(0, 0)
+                },
+                ExplosionEvent::BombDetonated {
+                    point: Point::new(0, 0),
+                    reach: 1,
+                    piercing: false
+                }
+            ]
+        );
+        assert_eq!(map.to_string(), "_ _\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_timed_bomb_chain() -> Result<(), Error> {
+        let mut map: Map = "B1 T2:3 _ F1\n".parse()?;
+        map.detonate_bomb(&Point::new(0, 0))?;
+
+        assert_eq!(map.to_string(), "_ _ _ _\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_preview_detonation() -> Result<(), Error> {
+        let map: Map = "B1 F2 _\nB2 _ F1\nF1 _ _\n".parse()?;
+        let (preview, summary) = map.preview_detonation(&Point::new(0, 0))?;
+
+        assert_eq!(
+            summary,
+            DetonationSummary {
+                enemies_killed: vec![Point::new(0, 2), Point::new(2, 1)],
+                enemies_damaged: vec![(Point::new(1, 0), 1)],
+                bombs_chained: vec![Point::new(0, 1)],
+            }
+        );
+        assert_eq!(preview.to_string(), "_ F1 _\n_ _ _\n_ _ _\n");
+        assert_eq!(map.to_string(), "B1 F2 _\nB2 _ F1\nF1 _ _\n");
+        assert_eq!(
+            map.preview_detonation(&Point::new(1, 1)),
+            Err(Error::Execution)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_blast_area() -> Result<(), Error> {
+        let map: Map = "_ B3 F1 R\n_ DR _ B1\nW _ _ _\n".parse()?;
+        let area = map.blast_area(&Point::new(1, 0))?;
+
+        assert_eq!(
+            area,
+            HashSet::from([
+                Point::new(0, 0),
+                Point::new(2, 0),
+                Point::new(1, 1),
+                Point::new(2, 1),
+                Point::new(3, 1),
+            ])
+        );
+        assert_eq!(map.at(&Point::new(1, 0)), &Item::Bomb(3));
+        assert_eq!(map.at(&Point::new(2, 0)), &Item::Enemy(1));
+
+        let piercing: Map = "_ S3 F1 R\n_ DR _ B1\nW _ _ _\n".parse()?;
+        assert!(piercing
+            .blast_area(&Point::new(1, 0))?
+            .contains(&Point::new(3, 0)));
+        assert_eq!(map.blast_area(&Point::new(0, 0)), Err(Error::Execution));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_blast_area_matches_detonation() -> Result<(), Error> {
+        let map: Map = "_ P1 F1 /\nB4 Y DU _\nR _ \\ P1\n".parse()?;
+        let area = map.blast_area(&Point::new(0, 1))?;
+
+        let mut detonated = map.clone();
+        let burned: HashSet<Point> = detonated
+            .detonate_bomb_traced(&Point::new(0, 1))?
+            .into_iter()
+            .filter_map(|event| match event {
+                ExplosionEvent::CellBurned { point } => Some(point),
+                _ => None,
+            })
+            .collect();
+
+        assert!(!area.is_empty());
+        assert_eq!(area, burned);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_parse() -> Result<(), Error> {
         let map = Map::new("test_dir/map_parse.txt")?;
 
         assert_eq!(map.at(&Point::new(0, 0)), &Item::Empty);
@@ -505,15 +2074,219 @@ mod tests {
     }
 
     #[test]
-    fn test_map_parse_invalid() -> Result<(), String> {
+    fn test_map_parse_invalid() -> Result<(), Error> {
         match Map::new("test_dir/map_invalid.txt") {
-            Ok(_) => assert!(false),
+            Ok(_) => panic!("el mapa invalido no deberia parsearse"),
             Err(err) => assert_eq!(
-                err,
+                err.to_string(),
                 "ERROR: [El archivo de entrada contiene un caracter invalido 'H']."
             ),
         }
 
         Ok(())
     }
+
+    #[test]
+    fn test_map_parse_error_position() {
+        let err = match Map::new("test_dir/map_invalid.txt") {
+            Ok(_) => panic!("el mapa invalido no deberia parsearse"),
+            Err(err) => err,
+        };
+
+        assert_eq!(err.position(), Some(Position::new(2, 3, 7)));
+        assert_eq!(err.kind(), &Error::InvalidToken(String::from("H")));
+
+        let err = match "B1  F4\n".parse::<Map>() {
+            Ok(_) => panic!("la vida del enemigo supera el maximo"),
+            Err(err) => err,
+        };
+        assert_eq!(err.position(), Some(Position::new(1, 5, 4)));
+    }
+
+    #[test]
+    fn test_map_parse_empty() {
+        assert_eq!(Map::new("test_dir/empty.txt").err(), Some(Error::EmptyMap));
+        assert_eq!("\n\n  \n".parse::<Map>().err(), Some(Error::EmptyMap));
+        assert_eq!(
+            "# solo comentarios\n".parse::<Map>().err(),
+            Some(Error::EmptyMap)
+        );
+        assert_eq!(Map::validate(""), vec![Error::EmptyMap]);
+    }
+
+    #[test]
+    fn test_map_parse_without_trailing_newline() -> Result<(), Error> {
+        let map = Map::new("test_dir/map_without_trailing_newline.txt")?;
+        assert_eq!(map.height(), 2);
+        assert_eq!(map.at(&Point::new(1, 1)), &Item::Enemy(2));
+
+        let err = Map::new("test_dir/one_line.txt").err();
+        assert_eq!(
+            err,
+            Some(Error::InvalidToken(String::from("1")).at("1", Position::new(1, 1, 0)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_parse_line_variations() -> Result<(), Error> {
+        let map: Map = "# mapa de prueba\r\nB1 F1\r\n\r\n  # comentario\n_\tF2\r\n\n\n".parse()?;
+
+        assert_eq!(map.height(), 2);
+        assert_eq!(map.width(), 2);
+        assert_eq!(format!("{}", map), "B1 F1\n_ F2\n");
+
+        assert_eq!(
+            "B1 F1\n\n# comentario\n_\n".parse::<Map>().err(),
+            Some(Error::RaggedRow {
+                row: 4,
+                expected: 2,
+                found: 1
+            })
+        );
+
+        let err = "B1 F1\r\n_ H\r\n".parse::<Map>().err();
+        assert_eq!(
+            err.and_then(|err| err.position()),
+            Some(Position::new(2, 3, 9))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_parse_ragged_rows() {
+        assert_eq!(
+            "_ _ _\n_ _\n_ _ _\n".parse::<Map>().err(),
+            Some(Error::RaggedRow {
+                row: 2,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            "_ _\n_ _ B1\n".parse::<Map>().err(),
+            Some(Error::RaggedRow {
+                row: 2,
+                expected: 2,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn test_map_parse_pad_short_rows() -> Result<(), Error> {
+        let options = ParseOptions {
+            pad_short_rows: true,
+            ..ParseOptions::default()
+        };
+        let mut map = Map::parse_with("B1 _\nF1 _ _\n_\n", &options)?;
+
+        assert_eq!(map.width(), 3);
+        assert_eq!(map.height(), 3);
+        assert_eq!(format!("{}", map), "B1 _ _\nF1 _ _\n_ _ _\n");
+
+        map.detonate_bomb(&Point::new(0, 0))?;
+        assert_eq!(map.at(&Point::new(0, 1)), &Item::Empty);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_from_rows() {
+        let map = Map::from_rows(vec![
+            vec![Item::Empty, Item::Wall],
+            vec![Item::Rock, Item::Empty],
+        ]);
+        assert!(map.is_ok_and(|map| map.width() == 2 && map.height() == 2));
+
+        assert_eq!(
+            Map::from_rows(vec![vec![Item::Empty, Item::Wall], vec![Item::Rock]]).err(),
+            Some(Error::RaggedRow {
+                row: 2,
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_map_validate() {
+        assert!(Map::validate("_ F1\nR S3\n").is_empty());
+
+        let errors = Map::validate("_ F0 H\nR S3 DZ\nB1 F4\n");
+        let kinds: Vec<&Error> = errors.iter().map(Error::kind).collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                &Error::NonPositiveNumber,
+                &Error::InvalidToken(String::from("H")),
+                &Error::InvalidDetour,
+                &Error::EnemyHealthOverLimit { health: 4, max: 3 },
+                &Error::RaggedRow {
+                    row: 3,
+                    expected: 3,
+                    found: 2
+                },
+            ]
+        );
+        assert_eq!(errors[2].position(), Some(Position::new(2, 6, 12)));
+
+        assert_eq!(
+            Map::validate("P1 P2\nP2 P2\n"),
+            vec![
+                Error::UnpairedTeleporter { id: 1, count: 1 },
+                Error::UnpairedTeleporter { id: 2, count: 3 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_line() {
+        assert_eq!(
+            tokenize_line("_  F1 DU", 3, 10),
+            vec![
+                ("_", Position::new(3, 1, 10)),
+                ("F1", Position::new(3, 4, 13)),
+                ("DU", Position::new(3, 7, 16)),
+            ]
+        );
+        assert!(tokenize_line("", 1, 0).is_empty());
+        assert_eq!(
+            tokenize_line("B1\tF2\r", 1, 0),
+            vec![
+                ("B1", Position::new(1, 1, 0)),
+                ("F2", Position::new(1, 4, 3))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_from_str() -> Result<(), Error> {
+        let map: Map = "_ F1\nR S3\n".parse()?;
+
+        assert_eq!(map.at(&Point::new(0, 0)), &Item::Empty);
+        assert_eq!(map.at(&Point::new(1, 0)), &Item::Enemy(1));
+        assert_eq!(map.at(&Point::new(0, 1)), &Item::Rock);
+        assert_eq!(map.at(&Point::new(1, 1)), &Item::PiercingBomb(3));
+
+        assert_eq!(
+            "_ F1\nR H\n".parse::<Map>().err(),
+            Some(Error::InvalidToken(String::from("H")).at("H", Position::new(2, 3, 7)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_map_from_reader() -> Result<(), Error> {
+        let map = Map::from_reader("B1 F2\nW DU\n".as_bytes())?;
+
+        assert_eq!(format!("{}", map), "B1 F2\nW DU\n");
+        assert_eq!(map.at(&Point::new(1, 1)), &Item::Detour(Direction::Up));
+
+        Ok(())
+    }
 }
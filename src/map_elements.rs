@@ -1,7 +1,12 @@
-use crate::{detour::Detour, utils::parse_greater_than_zero_u32};
+use crate::{
+    detour::Detour, error::Error, mirror::Mirror, rules::Rules, utils::parse_greater_than_zero_u32,
+};
 use std::fmt;
 
-#[derive(PartialEq, Debug)]
+/// Mayor numero que puede tener un teletransportador en el mapa.
+pub const MAX_TELEPORTER_ID: u32 = 9;
+
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
 pub enum Item {
     /// Los posibles items que pueden encontrarse en el mapa.
     Empty,
@@ -11,6 +16,16 @@ pub enum Item {
     Bomb(u32),
     PiercingBomb(u32),
     Detour(Detour),
+    /// Bomba con temporizador: alcance y turnos que faltan para que explote por si sola.
+    TimedBomb(u32, u32),
+    /// Roca con puntos de vida. Solo pierde vida si las reglas permiten destruir rocas, en otro caso se comporta como `Item::Rock`.
+    RockWithHealth(u32),
+    /// Teletransportador con su numero. Una rafaga que entra en el continua desde el otro teletransportador con el mismo numero.
+    Teleporter(u32),
+    /// Bomba cuya explosion se propaga en las 4 diagonales en lugar de las 4 direcciones ortogonales.
+    DiagonalBomb(u32),
+    /// Espejo o divisor que cambia la direccion de las rafagas.
+    Mirror(Mirror),
 }
 
 impl Item {
@@ -18,12 +33,30 @@ impl Item {
     /// # Arguments
     /// * `string` - String a parsear.
     /// # Returns
-    /// * `Result<Item, String>` - Item correspondiente al string o mensaje de error.
-    pub fn parse(string: &str) -> Result<Item, String> {
+    /// * `Result<Item, Error>` - Item correspondiente al string o error.
+    pub fn parse(string: &str) -> Result<Item, Error> {
+        Item::parse_with(string, &Rules::default())
+    }
+
+    /// Parsea un string y devuelve el item correspondiente, validandolo con las reglas indicadas.
+    /// # Arguments
+    /// * `string` - String a parsear.
+    /// * `rules` - Reglas con las que se jugara el mapa, por ejemplo la vida maxima de los enemigos.
+    /// # Returns
+    /// * `Result<Item, Error>` - Item correspondiente al string o error.
+    pub fn parse_with(string: &str, rules: &Rules) -> Result<Item, Error> {
         match string {
             "_" => Ok(Item::Empty),
             "W" => Ok(Item::Wall),
             "R" => Ok(Item::Rock),
+            "/" => Ok(Item::Mirror(Mirror::Slash)),
+            "\\" => Ok(Item::Mirror(Mirror::Backslash)),
+            "Y" => Ok(Item::Mirror(Mirror::Splitter)),
+            r if r.starts_with('R') => {
+                let (_, health_raw) = r.split_at(1);
+                let health = parse_greater_than_zero_u32(health_raw)?;
+                Ok(Item::RockWithHealth(health))
+            }
             d if d.starts_with('D') => {
                 let detour = Detour::parse(string)?;
                 Ok(Item::Detour(detour))
@@ -31,10 +64,11 @@ impl Item {
             f if f.starts_with('F') => {
                 let (_, health_raw) = f.split_at(1);
                 let health = parse_greater_than_zero_u32(health_raw)?;
-                if health > 3 {
-                    return Err(String::from(
-                        "ERROR: [La vida de los enemigos no puede ser mayor a 3].",
-                    ));
+                if health > rules.max_enemy_health {
+                    return Err(Error::EnemyHealthOverLimit {
+                        health,
+                        max: rules.max_enemy_health,
+                    });
                 }
                 Ok(Item::Enemy(health))
             }
@@ -48,13 +82,52 @@ impl Item {
                 let reach = parse_greater_than_zero_u32(reach_raw)?;
                 Ok(Item::PiercingBomb(reach))
             }
-            char => Err(
-                "ERROR: [El archivo de entrada contiene un caracter invalido '".to_owned()
-                    + char
-                    + "'].",
-            ),
+            t if t.starts_with('T') => {
+                let (_, properties) = t.split_at(1);
+                let (reach_raw, fuse_raw) =
+                    properties.split_once(':').ok_or(Error::InvalidNumber)?;
+                let reach = parse_greater_than_zero_u32(reach_raw)?;
+                let fuse = parse_greater_than_zero_u32(fuse_raw)?;
+                Ok(Item::TimedBomb(reach, fuse))
+            }
+            x if x.starts_with('X') => {
+                let (_, reach_raw) = x.split_at(1);
+                let reach = parse_greater_than_zero_u32(reach_raw)?;
+                Ok(Item::DiagonalBomb(reach))
+            }
+            p if p.starts_with('P') => {
+                let (_, id_raw) = p.split_at(1);
+                let id = parse_greater_than_zero_u32(id_raw)?;
+                if id > MAX_TELEPORTER_ID {
+                    return Err(Error::InvalidTeleporter {
+                        id,
+                        max: MAX_TELEPORTER_ID,
+                    });
+                }
+                Ok(Item::Teleporter(id))
+            }
+            char => Err(Error::InvalidToken(char.to_string())),
         }
     }
+
+    /// Devuelve true si el item es una bomba que puede detonarse.
+    pub fn is_bomb(&self) -> bool {
+        matches!(
+            self,
+            Item::Bomb(_) | Item::PiercingBomb(_) | Item::DiagonalBomb(_)
+        )
+    }
+
+    /// Devuelve true si el item es una roca, tenga o no puntos de vida.
+    pub fn is_rock(&self) -> bool {
+        matches!(self, Item::Rock | Item::RockWithHealth(_))
+    }
+
+    /// Devuelve true si el item explota al ser alcanzado por una rafaga. Incluye a las bombas con temporizador,
+    /// que no pueden elegirse para detonar pero si explotan en cadena.
+    pub fn is_explosive(&self) -> bool {
+        self.is_bomb() || matches!(self, Item::TimedBomb(..))
+    }
 }
 
 impl fmt::Display for Item {
@@ -67,6 +140,11 @@ impl fmt::Display for Item {
             Item::Bomb(r) => write!(f, "B{}", r),
             Item::PiercingBomb(r) => write!(f, "S{}", r),
             Item::Detour(d) => write!(f, "{}", d),
+            Item::TimedBomb(r, t) => write!(f, "T{}:{}", r, t),
+            Item::RockWithHealth(h) => write!(f, "R{}", h),
+            Item::Teleporter(id) => write!(f, "P{}", id),
+            Item::DiagonalBomb(r) => write!(f, "X{}", r),
+            Item::Mirror(m) => write!(f, "{}", m),
         }
     }
 }
@@ -87,10 +165,28 @@ mod tests {
         assert_eq!(format!("{}", Item::Detour(Detour::Down)), "DD");
         assert_eq!(format!("{}", Item::Detour(Detour::Left)), "DL");
         assert_eq!(format!("{}", Item::Detour(Detour::Right)), "DR");
+        assert_eq!(format!("{}", Item::TimedBomb(3, 2)), "T3:2");
+        assert_eq!(format!("{}", Item::RockWithHealth(2)), "R2");
+        assert_eq!(format!("{}", Item::Teleporter(3)), "P3");
+        assert_eq!(format!("{}", Item::DiagonalBomb(2)), "X2");
+        assert_eq!(format!("{}", Item::Mirror(Mirror::Backslash)), "\\");
+        assert_eq!(format!("{}", Item::Detour(Detour::UpLeft)), "DUL");
+    }
+
+    #[test]
+    fn test_item_is_bomb() {
+        assert!(Item::Bomb(1).is_bomb());
+        assert!(Item::PiercingBomb(1).is_bomb());
+        assert!(Item::DiagonalBomb(1).is_bomb());
+        assert!(!Item::Enemy(1).is_bomb());
+        assert!(!Item::Rock.is_bomb());
+        assert!(!Item::TimedBomb(1, 1).is_bomb());
+        assert!(Item::TimedBomb(1, 1).is_explosive());
+        assert!(Item::Bomb(1).is_explosive());
     }
 
     #[test]
-    fn test_item_parse() -> Result<(), String> {
+    fn test_item_parse() -> Result<(), Error> {
         assert_eq!(Item::parse("_")?, Item::Empty);
         assert_eq!(Item::parse("W")?, Item::Wall);
         assert_eq!(Item::parse("R")?, Item::Rock);
@@ -98,45 +194,53 @@ mod tests {
         assert_eq!(Item::parse("B4")?, Item::Bomb(4));
         assert_eq!(Item::parse("S4")?, Item::PiercingBomb(4));
         assert_eq!(Item::parse("DU")?, Item::Detour(Detour::Up));
+        assert_eq!(Item::parse("T3:2")?, Item::TimedBomb(3, 2));
+        assert_eq!(Item::parse("R2")?, Item::RockWithHealth(2));
+        assert_eq!(Item::parse("P9")?, Item::Teleporter(9));
+        assert_eq!(Item::parse("X2")?, Item::DiagonalBomb(2));
+        assert_eq!(Item::parse("/")?, Item::Mirror(Mirror::Slash));
+        assert_eq!(Item::parse("\\")?, Item::Mirror(Mirror::Backslash));
+        assert_eq!(Item::parse("Y")?, Item::Mirror(Mirror::Splitter));
+        assert_eq!(Item::parse("DDR")?, Item::Detour(Detour::DownRight));
 
-        assert_eq!(
-            Item::parse("F0"),
-            Err(
-                "ERROR: [El archivo de entrada contiene un numero menor o igual a cero]."
-                    .to_string()
-            )
-        );
+        assert_eq!(Item::parse("F0"), Err(Error::NonPositiveNumber));
 
         assert_eq!(
             Item::parse("F4"),
-            Err("ERROR: [La vida de los enemigos no puede ser mayor a 3].".to_string())
+            Err(Error::EnemyHealthOverLimit { health: 4, max: 3 })
         );
 
         assert_eq!(
             Item::parse("H"),
-            Err("ERROR: [El archivo de entrada contiene un caracter invalido 'H'].".to_string())
+            Err(Error::InvalidToken(String::from("H")))
         );
 
+        assert_eq!(Item::parse("Fb"), Err(Error::InvalidNumber));
+        assert_eq!(Item::parse("F-1"), Err(Error::InvalidNumber));
+        assert_eq!(Item::parse("D1"), Err(Error::InvalidDetour));
+        assert_eq!(Item::parse("T3"), Err(Error::InvalidNumber));
+        assert_eq!(Item::parse("T3:0"), Err(Error::NonPositiveNumber));
+        assert_eq!(Item::parse("R0"), Err(Error::NonPositiveNumber));
+        assert_eq!(Item::parse("P0"), Err(Error::NonPositiveNumber));
         assert_eq!(
-            Item::parse("Fb"),
-            Err(
-                "ERROR: [Error al interpretar puntos de vida/alcance en el archivo de entrada]."
-                    .to_string()
-            )
-        );
-        assert_eq!(
-            Item::parse("F-1"),
-            Err(
-                "ERROR: [Error al interpretar puntos de vida/alcance en el archivo de entrada]."
-                    .to_string()
-            )
+            Item::parse("P10"),
+            Err(Error::InvalidTeleporter { id: 10, max: 9 })
         );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_with_rules() -> Result<(), Error> {
+        let rules = Rules {
+            max_enemy_health: 5,
+            ..Rules::default()
+        };
+
+        assert_eq!(Item::parse_with("F5", &rules)?, Item::Enemy(5));
         assert_eq!(
-            Item::parse("D1"),
-            Err(
-                "ERROR: [El archivo de entrada contiene una direccion de desvio invalida]."
-                    .to_string()
-            )
+            Item::parse_with("F6", &rules),
+            Err(Error::EnemyHealthOverLimit { health: 6, max: 5 })
         );
 
         Ok(())
@@ -0,0 +1,81 @@
+use std::fmt;
+
+use crate::direction::Direction;
+
+/// Espejos que cambian la direccion de una rafaga al atravesarlos.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Mirror {
+    /// Espejo `/`: una rafaga hacia la derecha sale hacia arriba y una hacia abajo sale hacia la izquierda.
+    Slash,
+    /// Espejo `\`: una rafaga hacia la derecha sale hacia abajo y una hacia arriba sale hacia la izquierda.
+    Backslash,
+    /// Divisor `Y`: la rafaga sale en las dos direcciones perpendiculares a la de entrada.
+    Splitter,
+}
+
+impl Mirror {
+    /// Devuelve las direcciones en las que sale una rafaga que entra al espejo.
+    /// # Arguments
+    /// * `direction` - Direccion con la que la rafaga entra al espejo.
+    /// # Returns
+    /// * `Vec<Direction>` - Una direccion para los espejos y dos para el divisor.
+    pub fn reflect(&self, direction: Direction) -> Vec<Direction> {
+        let (dx, dy) = direction.offset();
+        let offsets = match self {
+            Mirror::Slash => vec![(-dy, -dx)],
+            Mirror::Backslash => vec![(dy, dx)],
+            Mirror::Splitter => vec![(-dy, dx), (dy, -dx)],
+        };
+        offsets
+            .into_iter()
+            .filter_map(Direction::from_offset)
+            .collect()
+    }
+}
+
+impl fmt::Display for Mirror {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mirror::Slash => write!(f, "/"),
+            Mirror::Backslash => write!(f, "\\"),
+            Mirror::Splitter => write!(f, "Y"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mirror_reflect() {
+        assert_eq!(Mirror::Slash.reflect(Direction::Right), vec![Direction::Up]);
+        assert_eq!(
+            Mirror::Slash.reflect(Direction::Down),
+            vec![Direction::Left]
+        );
+        assert_eq!(
+            Mirror::Backslash.reflect(Direction::Right),
+            vec![Direction::Down]
+        );
+        assert_eq!(
+            Mirror::Backslash.reflect(Direction::Up),
+            vec![Direction::Left]
+        );
+        assert_eq!(
+            Mirror::Splitter.reflect(Direction::Right),
+            vec![Direction::Down, Direction::Up]
+        );
+        assert_eq!(
+            Mirror::Splitter.reflect(Direction::UpRight),
+            vec![Direction::DownRight, Direction::UpLeft]
+        );
+    }
+
+    #[test]
+    fn test_mirror_display() {
+        assert_eq!(Mirror::Slash.to_string(), "/");
+        assert_eq!(Mirror::Backslash.to_string(), "\\");
+        assert_eq!(Mirror::Splitter.to_string(), "Y");
+    }
+}
@@ -0,0 +1,350 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    detour::Detour,
+    direction::Direction,
+    error::Error,
+    map::{Detonation, Map},
+    map_elements::Item,
+    point::Point,
+    rng::Rng,
+};
+
+/// Reglas de movimiento de los enemigos entre turnos. Los enemigos solo pueden moverse a celdas vacias,
+/// por lo que las paredes, rocas, bombas, desvios y otros enemigos los bloquean.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum MovementRule {
+    /// Los enemigos no se mueven.
+    #[default]
+    Static,
+    /// Los enemigos avanzan en la direccion indicada, o en su componente horizontal si es diagonal. Al chocar se dan vuelta, y al encontrar un desvio toman su direccion.
+    Patrol(Direction),
+    /// Cada enemigo se mueve a una celda vecina elegida al azar a partir de la semilla.
+    RandomWalk(u64),
+    /// Cada enemigo se acerca una celda a la bomba mas cercana.
+    ChaseNearestBomb,
+}
+
+impl MovementRule {
+    /// Parsea una regla de movimiento: `static`, `patrol:U`, `patrol:D`, `patrol:L`, `patrol:R`, `random:<semilla>` o `chase`.
+    /// # Arguments
+    /// * `string` - String a parsear.
+    /// # Returns
+    /// * `Result<MovementRule, Error>` - Regla parseada o error.
+    pub fn parse(string: &str) -> Result<MovementRule, Error> {
+        match string.split_once(':') {
+            None if string == "static" => Ok(MovementRule::Static),
+            None if string == "chase" => Ok(MovementRule::ChaseNearestBomb),
+            Some(("patrol", direction)) => Detour::parse(&format!("D{}", direction))
+                .ok()
+                .filter(|direction| Direction::ORTHOGONAL.contains(direction))
+                .map(MovementRule::Patrol)
+                .ok_or(Error::InvalidMovementRule),
+            Some(("random", seed)) => seed
+                .parse()
+                .map(MovementRule::RandomWalk)
+                .map_err(|_| Error::InvalidMovementRule),
+            _ => Err(Error::InvalidMovementRule),
+        }
+    }
+}
+
+/// Movimiento de un enemigo durante un turno.
+/// # Attributes
+/// * `from` - Celda en la que se encontraba el enemigo.
+/// * `to` - Celda a la que se movio.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EnemyMove {
+    pub from: Point,
+    pub to: Point,
+}
+
+impl fmt::Display for EnemyMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "EnemyMoved from_x={} from_y={} to_x={} to_y={}",
+            self.from.x, self.from.y, self.to.x, self.to.y
+        )
+    }
+}
+
+/// Aplica una regla de movimiento a los enemigos de un mapa, guardando el estado necesario entre turnos:
+/// la direccion de cada enemigo que patrulla y el generador de numeros aleatorios.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnemyMovement {
+    rule: MovementRule,
+    rng: Rng,
+    headings: HashMap<Point, Direction>,
+}
+
+impl EnemyMovement {
+    /// Crea el estado de movimiento para una regla. Los enemigos solo patrullan en direcciones ortogonales, por lo que
+    /// una patrulla diagonal se reemplaza por su componente horizontal.
+    /// # Arguments
+    /// * `rule` - Regla de movimiento de los enemigos
+    pub fn new(rule: MovementRule) -> Self {
+        let rule = match rule {
+            MovementRule::Patrol(direction) if !Direction::ORTHOGONAL.contains(&direction) => {
+                let (horizontal, _) = direction.offset();
+                MovementRule::Patrol(Direction::from_offset((horizontal, 0)).unwrap_or(direction))
+            }
+            rule => rule,
+        };
+        let seed = match rule {
+            MovementRule::RandomWalk(seed) => seed,
+            _ => 0,
+        };
+        EnemyMovement {
+            rule,
+            rng: Rng::new(seed),
+            headings: HashMap::new(),
+        }
+    }
+
+    /// Devuelve la regla de movimiento
+    pub fn rule(&self) -> MovementRule {
+        self.rule
+    }
+
+    /// Mueve a cada enemigo del mapa a lo sumo una celda. Los enemigos se resuelven recorriendo el mapa por filas,
+    /// y cada uno ve las celdas ya ocupadas por los que se movieron antes, por lo que el resultado es reproducible.
+    /// # Arguments
+    /// * `map` - Mapa sobre el que se mueven los enemigos
+    /// # Returns
+    /// * `Vec<EnemyMove>` - Movimientos realizados, en el orden en que se resolvieron
+    pub fn move_enemies(&mut self, map: &mut Map) -> Vec<EnemyMove> {
+        self.move_enemies_recorded(map, &mut Detonation::default())
+    }
+
+    /// Mueve a los enemigos como `move_enemies`, registrando cada celda que cambia para poder deshacer el movimiento
+    /// # Arguments
+    /// * `map` - Mapa sobre el que se mueven los enemigos
+    /// * `record` - Registro del turno al que se agregan los cambios de las celdas
+    /// # Returns
+    /// * `Vec<EnemyMove>` - Movimientos realizados, en el orden en que se resolvieron
+    pub fn move_enemies_recorded(
+        &mut self,
+        map: &mut Map,
+        record: &mut Detonation,
+    ) -> Vec<EnemyMove> {
+        let enemies: Vec<Point> = map
+            .points()
+            .filter(|point| matches!(map.at(point), Item::Enemy(_)))
+            .collect();
+        let mut headings = HashMap::new();
+        let mut moves = Vec::new();
+
+        for enemy in enemies {
+            let target = match self.rule {
+                MovementRule::Static => None,
+                MovementRule::Patrol(direction) => {
+                    let heading = self.headings.get(&enemy).copied().unwrap_or(direction);
+                    let (target, heading) = patrol_step(map, &enemy, heading);
+                    headings.insert(target.unwrap_or(enemy), heading);
+                    target
+                }
+                MovementRule::RandomWalk(_) => {
+                    let direction =
+                        Direction::ORTHOGONAL[self.rng.below(Direction::ORTHOGONAL.len())];
+                    free_neighbour(map, &enemy, &direction)
+                }
+                MovementRule::ChaseNearestBomb => chase_step(map, &enemy),
+            };
+
+            if let Some(to) = target {
+                map.move_item_recorded(&enemy, &to, record);
+                moves.push(EnemyMove { from: enemy, to });
+            }
+        }
+
+        self.headings = headings;
+        moves
+    }
+}
+
+/// Devuelve la celda vecina en una direccion si esta vacia.
+fn free_neighbour(map: &Map, point: &Point, direction: &Direction) -> Option<Point> {
+    map.get_next_point(point, direction)
+        .filter(|next| map.at(next) == &Item::Empty)
+}
+
+/// Calcula el paso de un enemigo que patrulla, devolviendo la celda a la que se mueve y su nueva direccion.
+/// Los enemigos patrullan solo en direcciones ortogonales, por lo que un desvio diagonal los hace dar vuelta como un obstaculo.
+fn patrol_step(map: &Map, enemy: &Point, heading: Direction) -> (Option<Point>, Direction) {
+    match map.get_next_point(enemy, &heading) {
+        Some(next) => match map.at(&next) {
+            Item::Empty => (Some(next), heading),
+            Item::Detour(detour_direction) if Direction::ORTHOGONAL.contains(detour_direction) => {
+                (None, *detour_direction)
+            }
+            _ => (None, heading.opposite()),
+        },
+        None => (None, heading.opposite()),
+    }
+}
+
+/// Calcula el paso de un enemigo hacia la bomba mas cercana segun la distancia Manhattan.
+/// Los empates entre bombas se resuelven por su posicion en el mapa, y se intenta primero el eje con mayor distancia.
+fn chase_step(map: &Map, enemy: &Point) -> Option<Point> {
+    let distance = |point: &Point| enemy.x.abs_diff(point.x) + enemy.y.abs_diff(point.y);
+    let target = map
+        .points()
+        .filter(|point| map.at(point).is_explosive())
+        .min_by_key(|point| distance(point))?;
+
+    let horizontal = match target.x.cmp(&enemy.x) {
+        std::cmp::Ordering::Less => Some(Direction::Left),
+        std::cmp::Ordering::Greater => Some(Direction::Right),
+        std::cmp::Ordering::Equal => None,
+    };
+    let vertical = match target.y.cmp(&enemy.y) {
+        std::cmp::Ordering::Less => Some(Direction::Up),
+        std::cmp::Ordering::Greater => Some(Direction::Down),
+        std::cmp::Ordering::Equal => None,
+    };
+    let candidates = if enemy.x.abs_diff(target.x) >= enemy.y.abs_diff(target.y) {
+        [horizontal, vertical]
+    } else {
+        [vertical, horizontal]
+    };
+
+    candidates
+        .into_iter()
+        .flatten()
+        .find_map(|direction| free_neighbour(map, enemy, &direction))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_movement_rule_parse() {
+        assert_eq!(MovementRule::parse("static"), Ok(MovementRule::Static));
+        assert_eq!(
+            MovementRule::parse("patrol:L"),
+            Ok(MovementRule::Patrol(Direction::Left))
+        );
+        assert_eq!(
+            MovementRule::parse("random:42"),
+            Ok(MovementRule::RandomWalk(42))
+        );
+        assert_eq!(
+            MovementRule::parse("chase"),
+            Ok(MovementRule::ChaseNearestBomb)
+        );
+        assert_eq!(
+            MovementRule::parse("patrol:X"),
+            Err(Error::InvalidMovementRule)
+        );
+        assert_eq!(
+            MovementRule::parse("patrol:UL"),
+            Err(Error::InvalidMovementRule)
+        );
+        assert_eq!(
+            MovementRule::parse("random:a"),
+            Err(Error::InvalidMovementRule)
+        );
+        assert_eq!(MovementRule::parse("fly"), Err(Error::InvalidMovementRule));
+    }
+
+    #[test]
+    fn test_patrol() -> Result<(), Error> {
+        let mut map: Map = "F1 _ W\n_ F2 DU\n".parse()?;
+        let mut movement = EnemyMovement::new(MovementRule::Patrol(Direction::Right));
+
+        let moves = movement.move_enemies(&mut map);
+        assert_eq!(
+            moves,
+            vec![EnemyMove {
+                from: Point::new(0, 0),
+                to: Point::new(1, 0)
+            }]
+        );
+        assert_eq!(map.to_string(), "_ F1 W\n_ F2 DU\n");
+
+        // El primer enemigo choca con la pared y el segundo, que tomo la direccion del desvio, con el primero.
+        assert!(movement.move_enemies(&mut map).is_empty());
+        movement.move_enemies(&mut map);
+        assert_eq!(map.to_string(), "F1 _ W\n_ F2 DU\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_diagonal_patrol_is_horizontal() -> Result<(), Error> {
+        let mut map: Map = "F1 _\n_ _\n".parse()?;
+        let mut movement = EnemyMovement::new(MovementRule::Patrol(Direction::DownRight));
+
+        assert_eq!(movement.rule(), MovementRule::Patrol(Direction::Right));
+        movement.move_enemies(&mut map);
+        assert_eq!(map.to_string(), "_ F1\n_ _\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_patrol_turns_around_at_diagonal_detours() -> Result<(), Error> {
+        let mut map: Map = "_ F1 DUR\n_ _ _\n".parse()?;
+        let mut movement = EnemyMovement::new(MovementRule::Patrol(Direction::Right));
+
+        assert!(movement.move_enemies(&mut map).is_empty());
+        movement.move_enemies(&mut map);
+        assert_eq!(map.to_string(), "F1 _ DUR\n_ _ _\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_patrol_is_blocked_by_rocks_and_bombs() -> Result<(), Error> {
+        let mut map: Map = "F1 R\nF1 B1\n".parse()?;
+        let mut movement = EnemyMovement::new(MovementRule::Patrol(Direction::Right));
+
+        assert!(movement.move_enemies(&mut map).is_empty());
+        assert_eq!(map.to_string(), "F1 R\nF1 B1\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_random_walk_is_reproducible() -> Result<(), Error> {
+        let map: Map = "_ _ _ _\n_ F1 F2 _\n_ _ _ _\n".parse()?;
+        let play = |seed| {
+            let mut map = map.clone();
+            let mut movement = EnemyMovement::new(MovementRule::RandomWalk(seed));
+            for _ in 0..5 {
+                movement.move_enemies(&mut map);
+            }
+            map
+        };
+
+        assert_eq!(play(7), play(7));
+        assert_eq!(play(7).count_items(|item| item != &Item::Empty), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_chase_nearest_bomb() -> Result<(), Error> {
+        let mut map: Map = "F1 _ _ B1\n_ _ _ _\nB2 _ _ F2\n".parse()?;
+        let mut movement = EnemyMovement::new(MovementRule::ChaseNearestBomb);
+
+        let moves = movement.move_enemies(&mut map);
+        assert_eq!(
+            moves,
+            vec![
+                EnemyMove {
+                    from: Point::new(0, 0),
+                    to: Point::new(0, 1)
+                },
+                EnemyMove {
+                    from: Point::new(3, 2),
+                    to: Point::new(3, 1)
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+/// Generador de numeros pseudoaleatorios SplitMix64. Es simple y rapido, y a partir de la misma semilla
+/// produce siempre la misma secuencia en cualquier plataforma, por lo que los resultados pueden reproducirse exactamente.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Crea un nuevo generador.
+    /// # Arguments
+    /// * `seed` - Semilla inicial.
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// Devuelve el siguiente numero de la secuencia.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Devuelve un numero en el rango [0, 1).
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Devuelve un numero en el rango [0, bound). Si `bound` es cero devuelve cero.
+    /// # Arguments
+    /// * `bound` - Limite superior, no incluido.
+    pub fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 {
+            return 0;
+        }
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Devuelve un numero en el rango [min, max]. Si `max` es menor a `min` devuelve `min`.
+    /// # Arguments
+    /// * `min` - Limite inferior, incluido.
+    /// * `max` - Limite superior, incluido.
+    pub fn between(&mut self, min: u32, max: u32) -> u32 {
+        if max <= min {
+            return min;
+        }
+        min + self.below((max - min + 1) as usize) as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng_is_reproducible() {
+        let mut first = Rng::new(42);
+        let mut second = Rng::new(42);
+        let mut other = Rng::new(43);
+
+        let sequence: Vec<u64> = (0..5).map(|_| first.next_u64()).collect();
+        assert_eq!(
+            sequence,
+            (0..5).map(|_| second.next_u64()).collect::<Vec<u64>>()
+        );
+        assert_ne!(
+            sequence,
+            (0..5).map(|_| other.next_u64()).collect::<Vec<u64>>()
+        );
+    }
+
+    #[test]
+    fn test_rng_ranges() {
+        let mut rng = Rng::new(7);
+        for _ in 0..100 {
+            assert!(rng.below(3) < 3);
+            assert!((1..=3).contains(&rng.between(1, 3)));
+            assert!((0.0..1.0).contains(&rng.next_f64()));
+        }
+        assert_eq!(rng.below(0), 0);
+        assert_eq!(rng.between(2, 2), 2);
+    }
+}
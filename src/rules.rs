@@ -0,0 +1,277 @@
+use crate::{error::Error, io, map_elements::Item};
+
+/// Reglas opcionales del juego. Los valores por defecto reproducen el comportamiento original,
+/// por lo que los mapas existentes se juegan igual si no se indica otra configuracion.
+/// # Attributes
+/// * `destructible_rocks` - Si es true, las rocas tienen puntos de vida y las rafagas que las alcanzan las desgastan
+///   hasta convertirlas en `Item::Empty`. Una roca `R` tiene 1 punto de vida y una roca `R2` tiene 2.
+/// * `rocks_stop_bombs` - Si es false, las rafagas de las bombas normales atraviesan las rocas como las de traspaso.
+/// * `bombs_chain` - Si es false, las rafagas pasan por encima de las bombas que alcanzan sin detonarlas.
+/// * `max_enemy_health` - Vida maxima que puede tener un enemigo al parsear el mapa.
+/// * `damage_policy` - Cuantas veces puede dañar una explosion a un mismo enemigo.
+/// * `bomb_damage` - Puntos de vida que quita cada tipo de bomba.
+/// * `damage_current_health` - Si es true, el daño se descuenta de la vida actual de cada enemigo, por lo que un enemigo
+///   alcanzado por varias bombas de una misma cadena recibe el daño de todas. Si es false, se descuenta de la vida que
+///   tenia al ser alcanzado, y el ultimo daño aplicado reemplaza a los anteriores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rules {
+    pub destructible_rocks: bool,
+    pub rocks_stop_bombs: bool,
+    pub bombs_chain: bool,
+    pub max_enemy_health: u32,
+    pub damage_policy: DamagePolicy,
+    pub bomb_damage: BombDamage,
+    pub damage_current_health: bool,
+}
+
+/// Vida maxima que puede tener un enemigo en el mapa con las reglas por defecto.
+pub const MAX_ENEMY_HEALTH: u32 = 3;
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            destructible_rocks: false,
+            rocks_stop_bombs: true,
+            bombs_chain: true,
+            max_enemy_health: MAX_ENEMY_HEALTH,
+            damage_policy: DamagePolicy::default(),
+            bomb_damage: BombDamage::default(),
+            damage_current_health: false,
+        }
+    }
+}
+
+impl Rules {
+    /// Lee las reglas desde un archivo. Ver `Rules::parse` para el formato.
+    /// # Arguments
+    /// * `input_file` - Nombre del archivo de reglas
+    /// # Returns
+    /// * `Result<Rules, Error>` - Reglas leidas o error si el archivo no puede leerse o alguna regla es invalida
+    pub fn from_file(input_file: &str) -> Result<Rules, Error> {
+        Rules::parse(&io::read_file(input_file)?)
+    }
+
+    /// Parsea un conjunto de reglas con un formato similar a TOML o INI: una regla `clave = valor` por linea,
+    /// comentarios que comienzan con `#` y la seccion `[damage]` para el daño de cada tipo de bomba.
+    /// Las reglas que no se indican conservan su valor por defecto.
+    ///
+    /// ```text
+    /// destructible_rocks = true
+    /// rocks_stop_bombs = true
+    /// bombs_chain = false
+    /// max_enemy_health = 5
+    /// damage_policy = "per_bomb"   # per_bomb, per_burst o per_chain
+    /// damage_current_health = true
+    ///
+    /// [damage]
+    /// bomb = 1
+    /// piercing_bomb = 2
+    /// timed_bomb = 1
+    /// diagonal_bomb = 1
+    /// ```
+    /// # Arguments
+    /// * `rules_raw` - Texto con las reglas
+    /// # Returns
+    /// * `Result<Rules, Error>` - Reglas parseadas o error con la primera linea invalida
+    pub fn parse(rules_raw: &str) -> Result<Rules, Error> {
+        let mut rules = Rules::default();
+        let mut section = "";
+
+        for (line_index, line) in rules_raw.lines().enumerate() {
+            let content = line.split('#').next().unwrap_or_default().trim();
+            if content.is_empty() {
+                continue;
+            }
+            if let Some(name) = content
+                .strip_prefix('[')
+                .and_then(|name| name.strip_suffix(']'))
+            {
+                section = name.trim();
+                continue;
+            }
+
+            let invalid = || Error::InvalidRule {
+                line: line_index + 1,
+                rule: content.to_string(),
+            };
+            let (key, value) = content.split_once('=').ok_or_else(invalid)?;
+            let value = value.trim().trim_matches('"');
+            rules.set(section, key.trim(), value).ok_or_else(invalid)?;
+        }
+        Ok(rules)
+    }
+
+    /// Cambia el valor de una regla, devolviendo None si la regla no existe o el valor es invalido.
+    fn set(&mut self, section: &str, key: &str, value: &str) -> Option<()> {
+        match (section, key) {
+            ("", "destructible_rocks") => self.destructible_rocks = value.parse().ok()?,
+            ("", "rocks_stop_bombs") => self.rocks_stop_bombs = value.parse().ok()?,
+            ("", "bombs_chain") => self.bombs_chain = value.parse().ok()?,
+            ("", "max_enemy_health") => {
+                self.max_enemy_health = value.parse().ok().filter(|health| *health > 0)?
+            }
+            ("", "damage_policy") => self.damage_policy = DamagePolicy::parse(value)?,
+            ("", "damage_current_health") => self.damage_current_health = value.parse().ok()?,
+            ("damage", "bomb") => {
+                self.bomb_damage.bomb = value.parse().ok().filter(|damage| *damage > 0)?
+            }
+            ("damage", "piercing_bomb") => {
+                self.bomb_damage.piercing_bomb = value.parse().ok().filter(|damage| *damage > 0)?
+            }
+            ("damage", "timed_bomb") => {
+                self.bomb_damage.timed_bomb = value.parse().ok().filter(|damage| *damage > 0)?
+            }
+            ("damage", "diagonal_bomb") => {
+                self.bomb_damage.diagonal_bomb = value.parse().ok().filter(|damage| *damage > 0)?
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+}
+
+/// Politica con la que se daña a los enemigos alcanzados por una explosion.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DamagePolicy {
+    /// Un enemigo recibe daño una vez por cada bomba que lo alcanza, aunque lo alcancen varias de sus rafagas.
+    PerBomb,
+    /// Un enemigo recibe daño una vez por cada rafaga que lo alcanza, por lo que una misma bomba puede dañarlo varias veces.
+    #[default]
+    PerBurst,
+    /// Un enemigo recibe daño una sola vez por reaccion en cadena. Si lo alcanzan bombas de distinto tipo,
+    /// recibe el mayor de sus daños.
+    PerChain,
+}
+
+impl DamagePolicy {
+    /// Parsea una politica de daño: `per_bomb`, `per_burst` o `per_chain`.
+    /// # Arguments
+    /// * `string` - String a parsear.
+    /// # Returns
+    /// * `Option<DamagePolicy>` - Politica parseada, o None si no existe.
+    pub fn parse(string: &str) -> Option<DamagePolicy> {
+        match string {
+            "per_bomb" => Some(DamagePolicy::PerBomb),
+            "per_burst" => Some(DamagePolicy::PerBurst),
+            "per_chain" => Some(DamagePolicy::PerChain),
+            _ => None,
+        }
+    }
+}
+
+/// Puntos de vida que quita a los enemigos cada tipo de bomba.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BombDamage {
+    pub bomb: u32,
+    pub piercing_bomb: u32,
+    pub timed_bomb: u32,
+    pub diagonal_bomb: u32,
+}
+
+impl Default for BombDamage {
+    fn default() -> Self {
+        BombDamage {
+            bomb: 1,
+            piercing_bomb: 1,
+            timed_bomb: 1,
+            diagonal_bomb: 1,
+        }
+    }
+}
+
+impl BombDamage {
+    /// Devuelve el daño que hace una bomba
+    /// # Arguments
+    /// * `bomb` - Item de la bomba
+    /// # Returns
+    /// * `u32` - Daño de la bomba, o 0 si el item no es una bomba
+    pub fn of(&self, bomb: &Item) -> u32 {
+        match bomb {
+            Item::Bomb(_) => self.bomb,
+            Item::PiercingBomb(_) => self.piercing_bomb,
+            Item::TimedBomb(..) => self.timed_bomb,
+            Item::DiagonalBomb(_) => self.diagonal_bomb,
+            _ => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bomb_damage_of() {
+        let damage = BombDamage {
+            piercing_bomb: 2,
+            ..BombDamage::default()
+        };
+
+        assert_eq!(damage.of(&Item::Bomb(3)), 1);
+        assert_eq!(damage.of(&Item::PiercingBomb(3)), 2);
+        assert_eq!(damage.of(&Item::TimedBomb(1, 2)), 1);
+        assert_eq!(damage.of(&Item::Enemy(1)), 0);
+    }
+
+    #[test]
+    fn test_rules_parse() -> Result<(), Error> {
+        let rules = Rules::parse(
+            "# Reglas de prueba\n\
+             destructible_rocks = true\n\
+             bombs_chain = false\n\
+             max_enemy_health = 5\n\
+             damage_policy = \"per_chain\" # una vez por cadena\n\
+             damage_current_health = true\n\
+             \n\
+             [damage]\n\
+             piercing_bomb = 2\n",
+        )?;
+
+        assert_eq!(
+            rules,
+            Rules {
+                destructible_rocks: true,
+                bombs_chain: false,
+                max_enemy_health: 5,
+                damage_policy: DamagePolicy::PerChain,
+                damage_current_health: true,
+                bomb_damage: BombDamage {
+                    piercing_bomb: 2,
+                    ..BombDamage::default()
+                },
+                ..Rules::default()
+            }
+        );
+        assert_eq!(Rules::parse("")?, Rules::default());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rules_parse_invalid() {
+        assert_eq!(
+            Rules::parse("bombs_chain = true\nbombs_chain = si\n"),
+            Err(Error::InvalidRule {
+                line: 2,
+                rule: String::from("bombs_chain = si")
+            })
+        );
+        assert_eq!(
+            Rules::parse("max_enemy_health = 0"),
+            Err(Error::InvalidRule {
+                line: 1,
+                rule: String::from("max_enemy_health = 0")
+            })
+        );
+        assert_eq!(
+            Rules::parse("[damage]\nbomb = 1\npiercing_bomb = 0\n"),
+            Err(Error::InvalidRule {
+                line: 3,
+                rule: String::from("piercing_bomb = 0")
+            })
+        );
+        assert!(Rules::parse("bomb = 2").is_err());
+        assert!(Rules::parse("gravity = true").is_err());
+        assert!(Rules::parse("destructible_rocks").is_err());
+    }
+}
@@ -0,0 +1,390 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::Error,
+    explosion::ExplosionEvent,
+    game,
+    map::{Detonation, Map},
+    map_elements::Item,
+    point::Point,
+};
+
+/// Resultado de simular la detonacion de una bomba.
+/// # Attributes
+/// * `point` - Punto de la bomba detonada.
+/// * `enemies_killed` - Cantidad de enemigos eliminados.
+/// * `damage_dealt` - Puntos de vida quitados a los enemigos en total.
+/// * `rocks_left` - Cantidad de rocas que quedan en el mapa luego de la explosion.
+/// * `cells_burned` - Cantidad de celdas distintas alcanzadas por la explosion.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct BombScore {
+    pub point: Point,
+    pub enemies_killed: usize,
+    pub damage_dealt: u32,
+    pub rocks_left: usize,
+    pub cells_burned: usize,
+}
+
+impl BombScore {
+    /// Calcula el puntaje de una detonacion a partir de su registro y del mapa resultante
+    /// # Arguments
+    /// * `point` - Punto de la bomba detonada
+    /// * `record` - Registro de la detonacion
+    /// * `result` - Mapa luego de la detonacion
+    pub fn from_detonation(point: Point, record: &Detonation, result: &Map) -> Self {
+        let enemies_killed = record
+            .events
+            .iter()
+            .filter(|event| matches!(event, ExplosionEvent::EnemyKilled { .. }))
+            .count();
+        let damage_dealt = record
+            .changes
+            .iter()
+            .map(|change| match (&change.before, &change.after) {
+                (Item::Enemy(before), Item::Enemy(after)) => before.saturating_sub(*after),
+                (Item::Enemy(before), _) => *before,
+                _ => 0,
+            })
+            .sum();
+        let cells_burned = record
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                ExplosionEvent::CellBurned { point } => Some(*point),
+                _ => None,
+            })
+            .collect::<HashSet<Point>>()
+            .len();
+
+        BombScore {
+            point,
+            enemies_killed,
+            damage_dealt,
+            rocks_left: result.count_items(Item::is_rock),
+            cells_burned,
+        }
+    }
+}
+
+/// Simula la detonacion de una bomba sobre una copia del mapa y calcula su puntaje
+/// # Arguments
+/// * `map` - Mapa sobre el que se simula la detonacion
+/// * `point` - Punto de la bomba a detonar
+/// # Returns
+/// * `Result<BombScore, Error>` - Puntaje de la detonacion, o el error en caso de que no haya una bomba en el punto
+pub fn score_bomb(map: &Map, point: &Point) -> Result<BombScore, Error> {
+    let mut simulated = map.clone();
+    let record = simulated.detonate_bomb_recorded(point)?;
+    Ok(BombScore::from_detonation(*point, &record, &simulated))
+}
+
+/// Simula la detonacion de cada bomba del mapa y las ordena de mejor a peor. Se prioriza la cantidad de enemigos eliminados,
+/// luego el daño total, luego la menor cantidad de rocas restantes y por ultimo la cantidad de celdas alcanzadas.
+/// Los empates se resuelven por la posicion de la bomba, recorriendo el mapa por filas.
+/// # Arguments
+/// * `map` - Mapa a analizar, no se modifica
+/// # Returns
+/// * `Vec<BombScore>` - Puntaje de cada bomba, de mejor a peor
+pub fn rank_bombs(map: &Map) -> Vec<BombScore> {
+    let mut scores: Vec<BombScore> = map
+        .bombs()
+        .iter()
+        .filter_map(|point| score_bomb(map, point).ok())
+        .collect();
+
+    scores.sort_by(|a, b| {
+        b.enemies_killed
+            .cmp(&a.enemies_killed)
+            .then(b.damage_dealt.cmp(&a.damage_dealt))
+            .then(a.rocks_left.cmp(&b.rocks_left))
+            .then(b.cells_burned.cmp(&a.cells_burned))
+            .then((a.point.y, a.point.x).cmp(&(b.point.y, b.point.x)))
+    });
+    scores
+}
+
+/// Limites de la busqueda de una secuencia de detonaciones.
+/// # Attributes
+/// * `max_nodes` - Cantidad maxima de estados del mapa a expandir.
+/// * `max_time` - Tiempo maximo de busqueda, sin limite si es None.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchBudget {
+    pub max_nodes: usize,
+    pub max_time: Option<Duration>,
+}
+
+impl Default for SearchBudget {
+    fn default() -> Self {
+        SearchBudget {
+            max_nodes: 100_000,
+            max_time: None,
+        }
+    }
+}
+
+/// Resultado de buscar una secuencia de detonaciones que elimine a todos los enemigos.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SolveOutcome {
+    /// Secuencia mas corta de bombas a detonar, en orden. Es vacia si el mapa no tiene enemigos.
+    Solved(Vec<Point>),
+    /// Se recorrieron todos los estados alcanzables, `explored` en total, y en ninguno se eliminaron todos los enemigos.
+    Unsolvable { explored: usize },
+    /// Se agoto el presupuesto de busqueda luego de expandir `explored` estados sin encontrar una solucion.
+    BudgetExceeded { explored: usize },
+}
+
+/// Indica si quedan enemigos en el mapa
+pub fn has_enemies(map: &Map) -> bool {
+    map.count_items(|item| matches!(item, Item::Enemy(_))) > 0
+}
+
+/// Busca la secuencia mas corta de detonaciones que elimina a todos los enemigos del mapa.
+/// Realiza una busqueda en anchura sobre los estados del mapa, descartando los estados ya visitados
+/// ya que distintos ordenes de detonacion pueden llevar al mismo mapa. Cada detonacion se juega como un turno completo,
+/// por lo que los temporizadores de las bombas avanzan entre jugada y jugada. Los enemigos se consideran inmoviles:
+/// la busqueda no aplica ninguna regla de movimiento, por lo que la secuencia solo es valida con `MovementRule::Static`.
+/// # Arguments
+/// * `map` - Mapa inicial, no se modifica
+/// * `budget` - Limites de la busqueda
+/// # Returns
+/// * `SolveOutcome` - Secuencia encontrada, prueba de que no existe o aviso de que se agoto el presupuesto
+pub fn solve_sequence(map: &Map, budget: &SearchBudget) -> SolveOutcome {
+    if !has_enemies(map) {
+        return SolveOutcome::Solved(Vec::new());
+    }
+
+    let start = Instant::now();
+    let mut visited: HashSet<Map> = HashSet::from([map.clone()]);
+    let mut queue: VecDeque<(Map, Vec<Point>)> = VecDeque::from([(map.clone(), Vec::new())]);
+    let mut explored = 0;
+
+    while let Some((state, sequence)) = queue.pop_front() {
+        let out_of_time = budget
+            .max_time
+            .is_some_and(|max_time| start.elapsed() >= max_time);
+        if explored >= budget.max_nodes || out_of_time {
+            return SolveOutcome::BudgetExceeded { explored };
+        }
+        explored += 1;
+
+        for bomb in state.bombs() {
+            let mut next = state.clone();
+            if game::execute_turn(&mut next, bomb).is_err() {
+                continue;
+            }
+            let mut next_sequence = sequence.clone();
+            next_sequence.push(bomb);
+
+            if !has_enemies(&next) {
+                return SolveOutcome::Solved(next_sequence);
+            }
+            if visited.insert(next.clone()) {
+                queue.push_back((next, next_sequence));
+            }
+        }
+    }
+
+    SolveOutcome::Unsolvable { explored }
+}
+
+/// Resultado de contar las soluciones de un mapa.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CountOutcome {
+    /// Cantidad de secuencias distintas que eliminan a todos los enemigos.
+    Counted(usize),
+    /// Se agoto el presupuesto de busqueda luego de expandir `explored` estados, por lo que no se conoce la cantidad.
+    BudgetExceeded { explored: usize },
+}
+
+/// Cuenta las secuencias distintas de a lo sumo `max_turns` detonaciones que eliminan a todos los enemigos.
+/// Una secuencia termina en cuanto no quedan enemigos, por lo que si `max_turns` es el largo de la solucion mas corta
+/// se cuentan exactamente las soluciones minimas. Al igual que `solve_sequence`, supone que los enemigos no se mueven.
+/// # Arguments
+/// * `map` - Mapa inicial, no se modifica
+/// * `max_turns` - Cantidad maxima de detonaciones de cada secuencia
+/// * `budget` - Limites de la busqueda. Cada estado distinto que se expande cuenta como un nodo
+/// # Returns
+/// * `CountOutcome` - Cantidad de soluciones o aviso de que se agoto el presupuesto
+pub fn count_solutions(map: &Map, max_turns: usize, budget: &SearchBudget) -> CountOutcome {
+    let mut search = SolutionCount {
+        budget,
+        start: Instant::now(),
+        explored: 0,
+        memo: HashMap::new(),
+    };
+    match search.count(map, max_turns) {
+        Some(count) => CountOutcome::Counted(count),
+        None => CountOutcome::BudgetExceeded {
+            explored: search.explored,
+        },
+    }
+}
+
+/// Estado de la busqueda de `count_solutions`, que recuerda cuantas soluciones tiene cada estado ya expandido.
+struct SolutionCount<'a> {
+    budget: &'a SearchBudget,
+    start: Instant,
+    explored: usize,
+    memo: HashMap<(Map, usize), usize>,
+}
+
+impl SolutionCount<'_> {
+    /// Devuelve la cantidad de soluciones desde un estado, o None si se agoto el presupuesto.
+    fn count(&mut self, map: &Map, max_turns: usize) -> Option<usize> {
+        if !has_enemies(map) {
+            return Some(1);
+        }
+        if max_turns == 0 {
+            return Some(0);
+        }
+        if let Some(count) = self.memo.get(&(map.clone(), max_turns)) {
+            return Some(*count);
+        }
+
+        let out_of_time = self
+            .budget
+            .max_time
+            .is_some_and(|max_time| self.start.elapsed() >= max_time);
+        if self.explored >= self.budget.max_nodes || out_of_time {
+            return None;
+        }
+        self.explored += 1;
+
+        let mut count = 0;
+        for bomb in map.bombs() {
+            let mut next = map.clone();
+            if game::execute_turn(&mut next, bomb).is_ok() {
+                count += self.count(&next, max_turns - 1)?;
+            }
+        }
+        self.memo.insert((map.clone(), max_turns), count);
+        Some(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_bomb() -> Result<(), Error> {
+        let map: Map = "B2 F2 F1\n_ R _\nF1 _ _\n".parse()?;
+        let score = score_bomb(&map, &Point::new(0, 0))?;
+
+        assert_eq!(
+            score,
+            BombScore {
+                point: Point::new(0, 0),
+                enemies_killed: 2,
+                damage_dealt: 3,
+                rocks_left: 1,
+                cells_burned: 4,
+            }
+        );
+        assert_eq!(map.at(&Point::new(0, 0)), &Item::Bomb(2));
+        assert_eq!(score_bomb(&map, &Point::new(1, 1)), Err(Error::Execution));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rank_bombs() -> Result<(), Error> {
+        let map: Map = "B1 F1 _ S2\nF2 _ R B1\n_ _ F1 _\n".parse()?;
+        let ranking = rank_bombs(&map);
+        let points: Vec<Point> = ranking.iter().map(|score| score.point).collect();
+
+        assert_eq!(
+            points,
+            vec![Point::new(0, 0), Point::new(3, 0), Point::new(3, 1)]
+        );
+        assert_eq!(ranking[0].enemies_killed, 1);
+        assert_eq!(ranking[0].damage_dealt, 2);
+        assert_eq!(ranking[1].enemies_killed, 1);
+        assert_eq!(ranking[1].damage_dealt, 1);
+        assert_eq!(map.at(&Point::new(1, 0)), &Item::Enemy(1));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_sequence() -> Result<(), Error> {
+        let map: Map = "B1 F2 B1\n_ _ _\nF1 _ B2\n".parse()?;
+
+        assert_eq!(
+            solve_sequence(&map, &SearchBudget::default()),
+            SolveOutcome::Solved(vec![Point::new(0, 0), Point::new(2, 2)])
+        );
+
+        let solved: Map = "_ B1\n".parse()?;
+        assert_eq!(
+            solve_sequence(&solved, &SearchBudget::default()),
+            SolveOutcome::Solved(Vec::new())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_solutions() -> Result<(), Error> {
+        let map: Map = "B1 F2 B1\n_ _ _\nF1 _ B2\n".parse()?;
+
+        let budget = SearchBudget::default();
+
+        assert_eq!(count_solutions(&map, 1, &budget), CountOutcome::Counted(0));
+        assert_eq!(count_solutions(&map, 2, &budget), CountOutcome::Counted(2));
+
+        let budget = SearchBudget {
+            max_nodes: 2,
+            max_time: None,
+        };
+        assert_eq!(
+            count_solutions(&map, 2, &budget),
+            CountOutcome::BudgetExceeded { explored: 2 }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_sequence_timed_bombs() -> Result<(), Error> {
+        let map = Map::new("test_dir/map_with_timed_bombs.txt")?;
+
+        assert_eq!(
+            solve_sequence(&map, &SearchBudget::default()),
+            SolveOutcome::Solved(vec![Point::new(0, 0), Point::new(0, 2)])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_sequence_unsolvable() -> Result<(), Error> {
+        let map: Map = "B1 W F1\nB1 _ _\n".parse()?;
+
+        assert_eq!(
+            solve_sequence(&map, &SearchBudget::default()),
+            SolveOutcome::Unsolvable { explored: 2 }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_sequence_budget() -> Result<(), Error> {
+        let map: Map = "B1 W F1\nB1 _ _\n".parse()?;
+        let budget = SearchBudget {
+            max_nodes: 1,
+            max_time: None,
+        };
+
+        assert_eq!(
+            solve_sequence(&map, &budget),
+            SolveOutcome::BudgetExceeded { explored: 1 }
+        );
+
+        Ok(())
+    }
+}
@@ -1,8 +1,9 @@
 use tp_1::{
-    game::{self, validate_input},
+    game::{self, validate_input, Game},
     io,
-    map::Map,
+    map::{Map, ParseOptions},
     point::Point,
+    rules::Rules,
 };
 
 #[test]
@@ -21,18 +22,16 @@ fn map_with_bomb_and_enemies() {
     let mut map = match Map::new(&input_file) {
         Ok(map) => map,
         Err(e) => {
-            io::write_error(&output_file, &e);
-            assert!(false);
-            return;
+            io::write_error(&output_file, &e.to_string());
+            panic!("{}", e);
         }
     };
 
     match game::execute_turn(&mut map, point_to_detonate) {
         Ok(new_map) => io::write_output(&output_file, new_map),
         Err(e) => {
-            io::write_error(&output_file, &e);
-            assert!(false);
-            return;
+            io::write_error(&output_file, &e.to_string());
+            panic!("{}", e);
         }
     };
 
@@ -67,18 +66,16 @@ fn map_with_piercing_and_detour() {
     let mut map = match Map::new(&input_file) {
         Ok(map) => map,
         Err(e) => {
-            io::write_error(&output_file, &e);
-            assert!(false);
-            return;
+            io::write_error(&output_file, &e.to_string());
+            panic!("{}", e);
         }
     };
 
     match game::execute_turn(&mut map, point_to_detonate) {
         Ok(new_map) => io::write_output(&output_file, new_map),
         Err(e) => {
-            io::write_error(&output_file, &e);
-            assert!(false);
-            return;
+            io::write_error(&output_file, &e.to_string());
+            panic!("{}", e);
         }
     };
 
@@ -112,18 +109,16 @@ fn map_with_enemy_between_detour() {
     let mut map = match Map::new(&input_file) {
         Ok(map) => map,
         Err(e) => {
-            io::write_error(&output_file, &e);
-            assert!(false);
-            return;
+            io::write_error(&output_file, &e.to_string());
+            panic!("{}", e);
         }
     };
 
     match game::execute_turn(&mut map, point_to_detonate) {
         Ok(new_map) => io::write_output(&output_file, new_map),
         Err(e) => {
-            io::write_error(&output_file, &e);
-            assert!(false);
-            return;
+            io::write_error(&output_file, &e.to_string());
+            panic!("{}", e);
         }
     };
 
@@ -165,10 +160,10 @@ fn map_life_4() {
     .unwrap();
 
     match Map::new(&input_file) {
-        Ok(_) => assert!(false),
+        Ok(_) => panic!("se esperaba un error"),
         Err(e) => {
             assert_eq!(
-                e,
+                e.to_string(),
                 "ERROR: [La vida de los enemigos no puede ser mayor a 3]."
             );
         }
@@ -191,19 +186,102 @@ fn coordenates_outside_map() {
     let mut map = match Map::new(&input_file) {
         Ok(map) => map,
         Err(e) => {
-            io::write_error(&output_file, &e);
-            assert!(false);
-            return;
+            io::write_error(&output_file, &e.to_string());
+            panic!("{}", e);
         }
     };
 
     match game::execute_turn(&mut map, point_to_detonate) {
-        Ok(_) => assert!(false),
+        Ok(_) => panic!("se esperaba un error"),
         Err(e) => {
             assert_eq!(
-                e,
+                e.to_string(),
                 "ERROR: [Las coordenadas a detonar no se encuentran en el mapa]."
             );
         }
     };
 }
+
+#[test]
+fn empty_map() {
+    let (input_file, output_file, _x, _y) = validate_input(vec![
+        String::from(""),
+        String::from("test_dir/empty.txt"),
+        String::from("test_dir/output"),
+        String::from("0"),
+        String::from("0"),
+    ])
+    .unwrap();
+
+    match Map::new(&input_file) {
+        Ok(_) => panic!("se esperaba un error"),
+        Err(e) => io::write_error(&output_file, &e.to_string()),
+    };
+
+    let file = io::read_file("test_dir/output/test_dir/empty.txt").unwrap();
+    assert_eq!(
+        file,
+        "ERROR: [El archivo de entrada no contiene ningun mapa]."
+    );
+}
+
+#[test]
+fn map_with_timed_bombs() {
+    let mut game = Game::new(Map::new("test_dir/map_with_timed_bombs.txt").unwrap());
+    let maps = game.play(&[Point::new(0, 0), Point::new(0, 2)]).unwrap();
+
+    assert_eq!(
+        maps[0].to_string(),
+        "_ W T1:1
+_ W F1
+B1 W _
+"
+    );
+    assert_eq!(
+        maps[1].to_string(),
+        "_ W _
+_ W _
+_ W _
+"
+    );
+}
+
+#[test]
+fn map_with_custom_rules() {
+    let options = ParseOptions {
+        rules: Rules::from_file("test_dir/rules.txt").unwrap(),
+        ..ParseOptions::default()
+    };
+    assert!(Map::new("test_dir/map_with_custom_rules.txt").is_err());
+
+    let mut game =
+        Game::new(Map::new_with("test_dir/map_with_custom_rules.txt", &options).unwrap());
+    let maps = game.play(&[Point::new(0, 0), Point::new(0, 1)]).unwrap();
+
+    assert_eq!(
+        maps[0].to_string(),
+        "_ _ F5
+B1 F4 _
+"
+    );
+    assert_eq!(
+        maps[1].to_string(),
+        "_ _ F5
+_ F2 _
+"
+    );
+}
+
+#[test]
+fn map_with_teleporters() {
+    let mut map = Map::new("test_dir/map_with_teleporters.txt").unwrap();
+    game::execute_turn(&mut map, Point::new(0, 0)).unwrap();
+
+    assert_eq!(
+        map.to_string(),
+        "_ P1 _ W
+_ W _ _
+_ _ P1 _
+"
+    );
+}